@@ -3,6 +3,9 @@ set -u
 
 SOLVER=$1
 TIMELIMIT=$2
+# The rest of arguments are passed to the solver. e.g. --restart glucose
+shift 2
+OPTIONS="$@"
 
 sat=0
 unsat=0
@@ -10,24 +13,23 @@ unknown=0
 total_ms=0
 trap "exit" 2
 
-echo "Time Limit... ${TIMELIMIT} s"
-mkdir benchmark
+echo "Time Limit... ${TIMELIMIT} s Options... ${OPTIONS}"
+mkdir -p benchmark
 for file in `find cnf/benchmark/*/*.cnf -type f`; do
 
     cnf=`basename $file`
     result="benchmark/"${cnf}_result.txt
-    echo "UNKNOWN" > $result
-    
     echo "Solving.... ${file}"
     start_ms=`date +%s%3N`
-    timeout ${TIMELIMIT}s ${SOLVER} $file > /dev/null 2>&1
+    timeout ${TIMELIMIT}s ${SOLVER} $file ${OPTIONS} > $result 2>&1
     end_ms=`date +%s%3N`
-    status=`head -n 1 $result`
-    if [ ${status} = "SAT" ]; then
+    status=`grep -m 1 -oE "^c (SAT|UNSAT)$" $result | cut -c 3-`
+    if [ "${status}" = "SAT" ]; then
         sat=`expr $sat + 1`
-    elif [ ${status} = "UNSAT" ]; then
+    elif [ "${status}" = "UNSAT" ]; then
         unsat=`expr $unsat + 1`
     else
+        status="UNKNOWN"
         unknown=`expr $unknown + 1`
     fi
     elasped_ms=`expr $end_ms - $start_ms`
@@ -39,12 +39,13 @@ impl<'a> Iterator for ClauseIterMut<'a> {
 pub struct Clause<'a> {
     flags: &'a mut Flags,
     data: &'a mut [ClauseWord],
-    extra: Option<&'a mut ClauseWord>,
+    /// [activity, lbd] for a learnt clause, empty otherwise.
+    extra: &'a mut [ClauseWord],
 }
 
 impl<'a> Clause<'a> {
     pub fn len(&self) -> usize {
-        self.data.len() as usize
+        self.data.len()
     }
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
@@ -55,8 +56,27 @@ impl<'a> Clause<'a> {
         self.flags.contains(Flags::LEARNT)
     }
     pub fn activity(&self) -> f32 {
-        self.flags.contains(Flags::LEARNT);
-        unsafe { self.extra.as_ref().expect("No extra").activity }
+        debug_assert!(self.learnt());
+        unsafe { self.extra[0].activity }
+    }
+    pub fn set_activity(&mut self, activity: f32) {
+        debug_assert!(self.learnt());
+        self.extra[0].activity = activity;
+    }
+    /// LBD(Literal Block Distance) is the number of distinct decision levels in a clause.
+    pub fn lbd(&self) -> u32 {
+        debug_assert!(self.learnt());
+        unsafe { self.extra[1].lbd }
+    }
+    pub fn set_lbd(&mut self, lbd: u32) {
+        debug_assert!(self.learnt());
+        self.extra[1].lbd = lbd;
+    }
+    pub fn used(&self) -> bool {
+        self.flags.contains(Flags::USED)
+    }
+    pub fn set_used(&mut self, used: bool) {
+        self.flags.set(Flags::USED, used);
     }
     pub fn deleted(&self) -> bool {
         self.flags.contains(Flags::DELTED)
@@ -76,11 +96,11 @@ impl<'a> Clause<'a> {
         debug_assert!(self.flags.contains(Flags::RELOCATED));
         unsafe { self.data[0].relocation }
     }
-    pub fn iter(&self) -> ClauseIter {
+    pub fn iter(&self) -> ClauseIter<'_> {
         debug_assert!(!self.flags.contains(Flags::RELOCATED));
         ClauseIter(self.data.iter())
     }
-    pub fn iter_mut(&mut self) -> ClauseIterMut {
+    pub fn iter_mut(&mut self) -> ClauseIterMut<'_> {
         debug_assert!(!self.flags.contains(Flags::RELOCATED));
         ClauseIterMut(self.data.iter_mut())
     }
@@ -9,6 +9,9 @@ use super::{
 };
 
 pub type CRef = Id<ClauseWord>;
+
+/// The number of extra words (activity and lbd) that a learnt clause has.
+const LEARNT_EXTRA: u32 = 2;
 impl PartialEq for CRef {
     fn eq(&self, other: &Self) -> bool {
         self.0 == other.0
@@ -37,8 +40,27 @@ impl ClauseAllocator {
         }
     }
 
+    pub fn with_capacity(n: usize) -> ClauseAllocator {
+        ClauseAllocator {
+            ra: RegionAllocator::with_capacity(n),
+            wasted: 0,
+        }
+    }
+
+    /// Returns the number of words in the region.
+    pub fn len(&self) -> usize {
+        self.ra.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ra.is_empty()
+    }
+    /// Returns the number of words that are occupied by deleted clauses.
+    pub fn wasted(&self) -> usize {
+        self.wasted as usize
+    }
+
     /// [flags, len, lit0, lit1, lit2]
-    /// [flags, len, lit0, lit1, lit2, extra]
+    /// [flags, len, lit0, lit1, lit2, activity, lbd]
     pub fn alloc(&mut self, lits: &[Lit], learnt: bool) -> CRef {
         let flags = if learnt { Flags::LEARNT } else { Flags::NONE };
         //flags
@@ -48,9 +70,12 @@ impl ClauseAllocator {
         for &lit in lits.iter() {
             self.ra.alloc(ClauseWord::from(lit));
         }
-        //activity
+        //activity and lbd
         if learnt {
             self.ra.alloc(ClauseWord::from(0.0f32));
+            self.ra.alloc(ClauseWord {
+                lbd: lits.len() as u32,
+            });
         }
         src
     }
@@ -60,37 +85,69 @@ impl ClauseAllocator {
         let len_clause = unsafe { self.ra.get(cref + 1).len };
         // flags + len + lit0 + lit1 + extra
         if flags.contains(Flags::LEARNT) {
-            1 + 1 + len_clause + 1
+            1 + 1 + len_clause + LEARNT_EXTRA
         } else {
             1 + 1 + len_clause
         }
     }
 
     pub fn free(&mut self, cref: CRef) {
-        let mut flags = unsafe { self.ra.get(cref).flags };
+        let flags = unsafe { &mut self.ra.get_mut(cref).flags };
         debug_assert!(!flags.contains(Flags::DELTED));
         flags.insert(Flags::DELTED);
         self.wasted += self.region_len(cref);
     }
 
-    pub fn get_mut(&mut self, cref: CRef) -> Clause {
+    /// Keep only the first `len` literals of a clause.
+    /// The extra words of a learnt clause are moved next to the remaining literals.
+    pub fn shrink(&mut self, cref: CRef, len: usize) {
+        let old_len = unsafe { self.ra.get(cref + 1).len } as usize;
+        debug_assert!(len <= old_len);
         let flags = unsafe { self.ra.get(cref).flags };
+        if flags.contains(Flags::LEARNT) {
+            for i in 0..LEARNT_EXTRA as usize {
+                *self.ra.get_mut(cref + (2 + len + i)) = *self.ra.get(cref + (2 + old_len + i));
+            }
+        }
+        self.ra.get_mut(cref + 1).len = len as u32;
+        self.wasted += (old_len - len) as u32;
+    }
+
+    /// Move a clause to `to` and returns the new `CRef`.
+    /// A clause that is already moved returns the same `CRef` as the first move.
+    pub fn reloc(&mut self, cref: CRef, to: &mut ClauseAllocator) -> CRef {
+        let mut clause = self.get_mut(cref);
+        debug_assert!(!clause.deleted());
+        if clause.relocated() {
+            return clause.relocation();
+        }
+        let learnt = clause.learnt();
+        let lits: Vec<Lit> = clause.iter().copied().collect();
+        let new_cref = to.alloc(&lits, learnt);
+        {
+            let mut new_clause = to.get_mut(new_cref);
+            new_clause.set_used(clause.used());
+            if learnt {
+                new_clause.set_activity(clause.activity());
+                new_clause.set_lbd(clause.lbd());
+            }
+        }
+        clause.relocate(new_cref);
+        new_cref
+    }
+
+    pub fn get_mut(&mut self, cref: CRef) -> Clause<'_> {
+        let len = self.region_len(cref);
         let len_clause = unsafe { self.ra.get(cref + 1).len };
-        let len = if flags.contains(Flags::LEARNT) {
-            1 + 1 + len_clause + 1
-        } else {
-            1 + 1 + len_clause
-        };
         let slice = self.ra.subslice_mut(cref, len as usize);
         let (flag_slice, slice) = slice.split_at_mut(1);
         let (_len_slice, slice) = slice.split_at_mut(1);
         let (data_slice, extra_slice) = slice.split_at_mut(len_clause as usize);
 
-        //eprintln!("{:?}", unsafe {len_slice[0].len});
         Clause {
             flags: unsafe { &mut flag_slice[0].flags },
             data: data_slice,
-            extra: extra_slice.first_mut(),
+            extra: extra_slice,
         }
     }
 }
@@ -134,6 +191,50 @@ mod tests {
                 assert_eq!(Lit::new(i as u32, true), *lit);
             }
             assert_eq!(clause.activity(), 0.0);
+            assert_eq!(clause.lbd(), n);
+        }
+
+        {
+            // free
+            let lits: Vec<Lit> = (0..3).map(|i| Lit::new(i, true)).collect();
+            let cref = ca.alloc(&lits, true);
+            assert!(!ca.get_mut(cref).deleted());
+            ca.free(cref);
+            assert!(ca.get_mut(cref).deleted());
+            assert_eq!(ca.wasted(), 2 + 3 + 2);
+        }
+
+        {
+            // shrink
+            let wasted = ca.wasted();
+            let lits: Vec<Lit> = (0..5).map(|i| Lit::new(i, false)).collect();
+            let cref = ca.alloc(&lits, true);
+            ca.get_mut(cref).set_lbd(4);
+            ca.get_mut(cref).set_activity(2.5);
+            ca.shrink(cref, 3);
+            assert_eq!(ca.wasted(), wasted + 2);
+            let clause = ca.get_mut(cref);
+            assert_eq!(clause.iter().copied().collect::<Vec<_>>(), lits[..3]);
+            assert_eq!(clause.lbd(), 4);
+            assert_eq!(clause.activity(), 2.5);
+        }
+
+        {
+            // reloc
+            let lits: Vec<Lit> = (0..4).map(|i| Lit::new(i, false)).collect();
+            let cref = ca.alloc(&lits, true);
+            ca.get_mut(cref).set_lbd(3);
+            ca.get_mut(cref).set_activity(1.5);
+
+            let mut to = ClauseAllocator::with_capacity(ca.len() - ca.wasted());
+            let new_cref = ca.reloc(cref, &mut to);
+            assert!(ca.reloc(cref, &mut to) == new_cref);
+            assert_eq!(to.len(), 2 + 4 + 2);
+            let clause = to.get_mut(new_cref);
+            assert!(clause.learnt());
+            assert_eq!(clause.lbd(), 3);
+            assert_eq!(clause.activity(), 1.5);
+            assert_eq!(clause.iter().copied().collect::<Vec<_>>(), lits);
         }
     }
 }
@@ -11,6 +11,11 @@ pub struct ClauseDB {
     clauses: Vec<CRef>,
     /// learnt clauses
     learnts: Vec<CRef>,
+    /// Parameters for the activity of learnt clauses
+    cla_inc: f32,
+    cla_decay: f32,
+    /// The fraction of wasted memory allowed before a garbage collection is triggered.
+    garbage_frac: f64,
 }
 
 impl Default for ClauseDB {
@@ -19,20 +24,19 @@ impl Default for ClauseDB {
             ca: ClauseAllocator::default(),
             clauses: Vec::default(),
             learnts: Vec::default(),
+            cla_inc: 1.0,
+            cla_decay: 0.999,
+            garbage_frac: 0.20,
         }
     }
 }
 
 impl ClauseDB {
     pub fn new() -> ClauseDB {
-        ClauseDB {
-            ca: ClauseAllocator::new(),
-            clauses: Vec::new(),
-            learnts: Vec::new(),
-        }
+        ClauseDB::default()
     }
 
-    pub fn get_mut(&mut self, cref: CRef) -> Clause {
+    pub fn get_mut(&mut self, cref: CRef) -> Clause<'_> {
         self.ca.get_mut(cref)
     }
 
@@ -48,4 +52,145 @@ impl ClauseDB {
     pub fn free(&mut self, cref: CRef) {
         self.ca.free(cref);
     }
+
+    /// Keep only the first `len` literals of a clause. See `ClauseAllocator::shrink`.
+    pub fn shrink(&mut self, cref: CRef, len: usize) {
+        self.ca.shrink(cref, len);
+    }
+
+    /// Remove a deleted learnt clause from `learnts`.
+    /// It is cheap when the clause is one of the most recent learnt clauses.
+    pub fn remove_learnt(&mut self, cref: CRef) {
+        if let Some(idx) = self.learnts.iter().rposition(|&c| c == cref) {
+            self.learnts.remove(idx);
+        }
+    }
+
+    pub fn num_clauses(&self) -> usize {
+        self.clauses.len()
+    }
+    pub fn num_learnts(&self) -> usize {
+        self.learnts.len()
+    }
+    pub fn clauses(&self) -> &[CRef] {
+        &self.clauses
+    }
+    pub fn learnts(&self) -> &[CRef] {
+        &self.learnts
+    }
+
+    /// Drop original clauses that are already deleted from `clauses`.
+    pub fn retain_clauses(&mut self) {
+        let ca = &mut self.ca;
+        self.clauses.retain(|&cref| !ca.get_mut(cref).deleted());
+    }
+
+    /// Drop learnt clauses that are already deleted from `learnts`.
+    pub fn retain_learnts(&mut self) {
+        let ca = &mut self.ca;
+        self.learnts.retain(|&cref| !ca.get_mut(cref).deleted());
+    }
+
+    pub fn set_garbage_frac(&mut self, garbage_frac: f64) {
+        self.garbage_frac = garbage_frac;
+    }
+
+    /// Returns true if deleted clauses waste too much memory.
+    pub fn needs_gc(&self) -> bool {
+        self.ca.wasted() as f64 > self.ca.len() as f64 * self.garbage_frac
+    }
+
+    /// Returns an empty allocator that live clauses are moved to.
+    pub fn gc_allocator(&self) -> ClauseAllocator {
+        ClauseAllocator::with_capacity(self.ca.len() - self.ca.wasted())
+    }
+
+    /// Move a clause to `to`. See `ClauseAllocator::reloc`.
+    pub fn reloc(&mut self, cref: CRef, to: &mut ClauseAllocator) -> CRef {
+        self.ca.reloc(cref, to)
+    }
+
+    /// Move all live clauses to `to` and replace the allocator with it.
+    /// All `CRef`s held outside of `ClauseDB` must be relocated by `reloc` before.
+    pub fn finish_gc(&mut self, mut to: ClauseAllocator) {
+        for crefs in [&mut self.clauses, &mut self.learnts] {
+            let ca = &mut self.ca;
+            crefs.retain(|&cref| !ca.get_mut(cref).deleted());
+            for cref in crefs.iter_mut() {
+                *cref = ca.reloc(*cref, &mut to);
+            }
+        }
+        self.ca = to;
+    }
+
+    pub fn bump_activity(&mut self, cref: CRef) {
+        let mut clause = self.ca.get_mut(cref);
+        debug_assert!(clause.learnt());
+        let activity = clause.activity() + self.cla_inc;
+        clause.set_activity(activity);
+        if activity > 1e20 {
+            for &cref in self.learnts.iter() {
+                let mut clause = self.ca.get_mut(cref);
+                let activity = clause.activity();
+                clause.set_activity(activity * 1e-20);
+            }
+            self.cla_inc *= 1e-20;
+        }
+    }
+
+    pub fn decay_activity(&mut self) {
+        self.cla_inc /= self.cla_decay;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::lit::Lit;
+
+    use super::ClauseDB;
+
+    #[test]
+    fn test_clause_activity() {
+        let mut db = ClauseDB::new();
+        let lits: Vec<Lit> = (0..3).map(|i| Lit::new(i, true)).collect();
+        let c0 = db.alloc(&lits, true);
+        let c1 = db.alloc(&lits, true);
+        db.bump_activity(c0);
+        db.decay_activity();
+        db.bump_activity(c1);
+        assert!(db.get_mut(c1).activity() > db.get_mut(c0).activity());
+
+        // rescale
+        for _ in 0..50_000 {
+            db.decay_activity();
+        }
+        db.bump_activity(c1);
+        assert!(db.get_mut(c1).activity() <= 1e20);
+        assert!(db.get_mut(c1).activity() > db.get_mut(c0).activity());
+
+        db.free(c0);
+        db.retain_learnts();
+        assert_eq!(db.num_learnts(), 1);
+    }
+
+    #[test]
+    fn test_garbage_collect() {
+        let mut db = ClauseDB::new();
+        let crefs: Vec<_> = (0..10)
+            .map(|i| {
+                let lits: Vec<Lit> = (0..3).map(|j| Lit::new(i + j, true)).collect();
+                db.alloc(&lits, i % 2 == 0)
+            })
+            .collect();
+        for &cref in crefs.iter().take(6) {
+            db.free(cref);
+        }
+        assert!(db.needs_gc());
+        let mut to = db.gc_allocator();
+        let c9 = db.reloc(crefs[9], &mut to);
+        db.finish_gc(to);
+        assert!(!db.needs_gc());
+        assert_eq!(db.num_clauses() + db.num_learnts(), 4);
+        assert_eq!(db.get_mut(c9)[0], Lit::new(9, true));
+    }
 }
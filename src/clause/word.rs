@@ -13,6 +13,8 @@ pub union ClauseWord {
     pub lit: Lit,
     /// only for learnt clause
     pub activity: f32,
+    /// only for learnt clause
+    pub lbd: u32,
     pub relocation: CRef,
 }
 
@@ -50,6 +52,8 @@ bitflags! {
         const LEARNT = 0b00000010;
         /// A clause is relocated
         const RELOCATED = 0b00000100;
+        /// A learnt clause is used in conflict analysis since the last reduction
+        const USED = 0b00001000;
     }
 }
 
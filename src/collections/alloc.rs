@@ -17,7 +17,7 @@ impl<T> std::ops::Add<usize> for Id<T> {
 }
 
 impl<T> Id<T> {
-    pub const UNDEF: Id<T> = Id(std::u32::MAX, PhantomData);
+    pub const UNDEF: Id<T> = Id(u32::MAX, PhantomData);
 }
 
 impl<T> Default for Id<T> {
@@ -28,18 +28,30 @@ impl Heap {
         Heap::default()
     }
 
-    fn gt(&self, left: Var, right: Var) -> bool {
+    /// Returns true if `left` is popped before `right`.
+    pub fn gt(&self, left: Var, right: Var) -> bool {
         self.activity[left] > self.activity[right]
     }
 
-    #[allow(dead_code)]
-    fn top(self) -> Option<Var> {
+    pub fn top(&self) -> Option<Var> {
         if self.heap.is_empty() {
             return None;
         }
         Some(self.heap[0])
     }
 
+    /// Returns the top variable that is not assigned.
+    /// Assigned variables on the top are removed like `pop`.
+    pub fn top_unassigned(&mut self, assigned: &dyn Fn(Var) -> bool) -> Option<Var> {
+        while let Some(v) = self.top() {
+            if !assigned(v) {
+                return Some(v);
+            }
+            self.pop();
+        }
+        None
+    }
+
     #[allow(dead_code)]
     fn update(&mut self, v: Var) {
         if !self.in_heap(v) {
@@ -134,6 +146,19 @@ impl Heap {
         self.bump_inc /= self.decay_ratio;
     }
 
+    /// Set the activity of `v` and keep the heap order.
+    pub fn set_activity(&mut self, v: Var, activity: f64) {
+        let old = self.activity[v];
+        self.activity[v] = activity;
+        if let Some(idx) = self.indices[v] {
+            if activity > old {
+                self.up(idx);
+            } else {
+                self.down(idx);
+            }
+        }
+    }
+
     pub fn bump_activity(&mut self, v: Var) {
         self.activity[v] += self.bump_inc;
 
@@ -12,7 +12,7 @@ pub trait Idx {
     fn idx(&self) -> usize;
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct IdxVec<T: Idx, V> {
     data: Vec<V>,
     _markder: PhantomData<T>,
@@ -37,7 +37,6 @@ impl<T: Idx, V> IdxVec<T, V> {
 }
 
 /// Trait Implementation for `Lit` and `Var`
-
 impl<T: Idx, V> Deref for IdxVec<T, V> {
     type Target = [V];
     fn deref(&self) -> &Self::Target {
@@ -70,7 +69,6 @@ impl<Lit: Idx, V> IndexMut<Lit> for LitVec<V> {
     }
 }
 /// Var
-
 impl Idx for Var {
     fn idx(&self) -> usize {
         self.val() as usize
@@ -1,9 +1,20 @@
 use std::sync::mpsc::{self, Receiver};
 
-use data::VarData;
-use luby::LubyRestart;
+use card::Cards;
+use config::Config;
+use data::{Reason, VarData};
+use elim::Eliminator;
 use mpsc::Sender;
+use probe::Probe;
+use reduce::{ReduceSchedule, CORE_LBD};
+use restart::RestartPolicy;
+use stats::Stats;
+use subsume::Subsume;
+use symmetry::Symmetry;
+use unhide::Unhide;
+use vivify::Vivify;
 use watcher::{Watch, Watchers};
+use xor::Gauss;
 
 use crate::{
     clause::{alloc::CRef, db::ClauseDB},
@@ -12,57 +23,143 @@ use crate::{
 
 mod analyzer;
 mod assign;
+mod blocked;
+pub mod branching;
+mod card;
+mod chrono;
+pub mod config;
 mod data;
+mod elim;
+mod equiv;
+mod explain;
+mod gate;
+mod gc;
 mod luby;
+pub mod maxsat;
+mod minimize;
+pub mod optimize;
+mod otfs;
+pub mod phase;
+mod probe;
+mod reduce;
+pub mod restart;
+pub mod stats;
+mod subsume;
+mod symmetry;
+#[cfg(test)]
+mod testing;
+mod unhide;
+mod vivify;
+mod vmtf;
 mod watcher;
+mod xor;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// `Conflict` is a clause whose literals are all false.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Conflict {
+    Clause(CRef),
+    /// A binary clause, or a unit clause if both literals are the same.
+    Binary(Lit, Lit),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub enum SatResult {
     Sat,
     Unsat,
+    #[default]
     Unknown,
 }
 
-impl Default for SatResult {
-    fn default() -> Self {
-        SatResult::Unknown
-    }
-}
-
 pub struct Solver {
     db: ClauseDB,
     vardata: VarData,
     /// check clauses if a propagation or conflict happens.
     watches: Watchers,
     result: SatResult,
-    luby: LubyRestart,
+    restart: Box<dyn RestartPolicy>,
+    reduce: ReduceSchedule,
+    /// the threshold of chronological backtracking
+    chrono: Option<u32>,
+    /// keep decisions that would be made again on restarts
+    reuse_trail: bool,
+    /// replace literals at the same level in a learnt clause by the UIP of the level
+    shrink: bool,
+    /// strengthen reason clauses that are subsumed by resolvents
+    otfs: bool,
+    /// the number of recent learnt clauses checked by eager subsumption
+    eager_subsume: usize,
+    /// `None` disables vivification
+    vivify: Option<Vivify>,
+    /// `None` disables failed literal probing
+    probe: Option<Probe>,
+    /// `None` disables subsumption before search and between restarts
+    subsume: Option<Subsume>,
+    /// `None` disables unhiding
+    unhide: Option<Unhide>,
+    /// bounded variable elimination and the reconstruction of eliminated variables
+    elim: Eliminator,
+    /// XOR constraints propagated by Gauss-Jordan elimination
+    gauss: Gauss,
+    /// cardinality constraints propagated by counting true literals
+    cards: Cards,
+    /// symmetries broken by lex-leader constraints before the first search
+    symmetry: Symmetry,
+    /// the number of watches visited by `propagate`, which measures the effort of inprocessing
+    ticks: u64,
+    pub stats: Stats,
     pub sender: Sender<i32>,
     pub receiver: Receiver<i32>,
     pub interreupt: bool,
     pub models: Vec<LitBool>,
+    /// assumptions of the current `solve_with_assumptions`
+    assumptions: Vec<Lit>,
+    /// A subset of assumptions that makes the formula unsatisfiable.
+    pub failed_assumptions: Vec<Lit>,
 }
 
 impl Default for Solver {
     fn default() -> Self {
+        Solver::with_config(Config::default())
+    }
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver::default()
+    }
+
+    pub fn with_config(config: Config) -> Solver {
         let (sender, receiver) = mpsc::channel();
         Solver {
             db: ClauseDB::new(),
-            vardata: VarData::new(),
+            vardata: VarData::new(config.branching.build(), config.phase),
             watches: Watchers::new(),
             result: SatResult::Unknown,
-            luby: LubyRestart::default(),
+            restart: config.restart.build(),
+            reduce: ReduceSchedule::default(),
+            chrono: config.chrono,
+            reuse_trail: config.reuse_trail,
+            shrink: config.shrink,
+            otfs: config.otfs,
+            eager_subsume: config.eager_subsume,
+            vivify: config.vivify.then(Vivify::default),
+            probe: config.probe.then(Probe::default),
+            subsume: config.subsume.then(Subsume::default),
+            unhide: config.unhide.then(Unhide::default),
+            elim: Eliminator::new(&config),
+            gauss: Gauss::new(config.xor),
+            cards: Cards::new(config.card),
+            symmetry: Symmetry::new(config.symmetry),
+            ticks: 0,
+            stats: Stats::default(),
             sender,
             receiver,
             interreupt: false,
             models: Vec::new(),
+            assumptions: Vec::new(),
+            failed_assumptions: Vec::new(),
         }
     }
-}
-
-impl Solver {
-    pub fn new() -> Solver {
-        Solver::default()
-    }
 
     fn simplify_clause(&self, lits: &[Lit]) -> (bool, Vec<Lit>) {
         let mut lits = lits.to_vec();
@@ -103,12 +200,57 @@ impl Solver {
         (false, lits)
     }
 
-    pub fn propagate(&mut self) -> CRef {
+    /// Propagate assignments by clauses, cardinality constraints and XOR constraints
+    /// until a conflict or a fixpoint.
+    pub fn propagate(&mut self) -> Option<Conflict> {
+        // Literals that will be propagated again are counted again.
+        self.cards.uncount(self.vardata.trail.peek_head);
+        loop {
+            let head = self.vardata.trail.peek_head;
+            if let Some(confl) = self.propagate_clauses() {
+                return Some(confl);
+            }
+            // Matrices are checked only if their variables are assigned.
+            let touched = self.gauss.touch(&self.vardata.trail.stack[head..]);
+            match self.propagate_cards() {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(confl) => return Some(confl),
+            }
+            if !touched {
+                return None;
+            }
+            match self.propagate_xors() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(confl) => return Some(confl),
+            }
+        }
+    }
+
+    /// Propagate assignments by clauses until a conflict or a fixpoint.
+    fn propagate_clauses(&mut self) -> Option<Conflict> {
         while self.vardata.trail.peekable() {
             let p = self.vardata.trail.peek();
             self.vardata.trail.advance();
+            self.stats.propagations += 1;
+
+            // Binary clauses never touch `ClauseDB`.
+            for bw in self.watches.lookup_binary(p).iter() {
+                match self.vardata.eval(bw.other) {
+                    LitBool::True => {}
+                    LitBool::False => return Some(Conflict::Binary(!p, bw.other)),
+                    _ => self.vardata.enqueue_at(
+                        bw.other,
+                        self.vardata.level(p.var()),
+                        Reason::Binary(!p),
+                    ),
+                }
+            }
+
             let watchers_ptr = self.watches.as_mut_ptr();
             let ws = self.watches.lookup_mut(p);
+            self.ticks += 1 + ws.len() as u64;
             let mut idx = 0;
 
             'next_clause: while idx < ws.len() {
@@ -140,33 +282,65 @@ impl Solver {
                     if self.vardata.eval(lit) != LitBool::False {
                         clause.swap(1, k);
                         ws.swap_remove(idx);
-                        unsafe { &mut (*watchers_ptr)[!clause[1]].push(w) };
+                        unsafe { (&mut *watchers_ptr)[!clause[1]].push(w) };
                         continue 'next_clause;
                     }
                 }
                 ws[idx] = w;
                 if self.vardata.eval(first) == LitBool::False {
-                    return cref;
-                } else {
-                    self.vardata.enqueue(first, cref);
+                    return Some(Conflict::Clause(cref));
+                }
+                // `first` is implied at the highest level of the other literals,
+                // which is lower than the current one if `p` was assigned out of order.
+                let mut max_idx = 1;
+                let mut max_level = self.vardata.level(p.var());
+                if max_level < self.vardata.trail.decision_level() {
+                    for k in 2..clause.len() {
+                        let level = self.vardata.level(clause[k].var());
+                        if level > max_level {
+                            max_idx = k;
+                            max_level = level;
+                        }
+                    }
+                }
+                self.vardata
+                    .enqueue_at(first, max_level, Reason::Clause(cref));
+                if max_idx != 1 {
+                    // Watch the literal that is unassigned first by backtracking.
+                    clause.swap(1, max_idx);
+                    ws.swap_remove(idx);
+                    let w = Watch::new(cref, first);
+                    unsafe { (&mut *watchers_ptr)[!clause[1]].push(w) };
+                    continue 'next_clause;
                 }
                 idx += 1;
             }
         }
-        CRef::UNDEF
+        None
     }
-    fn new_var(&mut self) {
+    /// Add a new variable and returns it.
+    pub fn new_var(&mut self) -> var::Var {
+        let v = var::Var::from_idx(self.vardata.num_var());
         self.vardata.new_var();
         self.watches.new_var();
+        self.elim.new_var();
+        self.cards.new_var();
+        v
     }
 
+    /// Returns the number of variables.
+    pub fn num_var(&self) -> usize {
+        self.vardata.num_var()
+    }
+
+    /// Add a clause to the formula.
+    /// It can be called between `solve` calls and learnt clauses, activities and phases are kept.
     pub fn add_clause(&mut self, lits: &[Lit]) {
-        debug_assert!(self.vardata.trail.decision_level() == 0);
-        lits.iter().for_each(|lit| {
-            while lit.var().val() >= self.vardata.num_var() as u32 {
-                self.new_var();
-            }
-        });
+        if self.result == SatResult::Unsat {
+            // Adding clauses never makes an unsatisfiable formula satisfiable.
+            return;
+        }
+        self.prepare_constraint(lits);
 
         let (skip, lits) = self.simplify_clause(lits);
 
@@ -181,44 +355,94 @@ impl Solver {
                 self.result = SatResult::Unsat;
                 return;
             }
-            self.vardata.enqueue(lits[0], CRef::UNDEF);
-            if self.propagate() != CRef::UNDEF {
+            self.vardata.enqueue(lits[0], Reason::Undef);
+            if self.propagate().is_some() {
                 self.result = SatResult::Unsat;
             }
+        } else if lits.len() == 2 {
+            self.watches.watch_binary(lits[0], lits[1], false);
         } else {
             let cref = self.db.alloc(&lits, false);
             self.watches.watch(&lits, cref);
         }
     }
-    fn analyze(&mut self, confl: CRef) -> u32 {
+
+    /// Prepare to add a constraint over `lits` between `solve` calls.
+    /// New variables are added and eliminated variables are restored.
+    fn prepare_constraint(&mut self, lits: &[Lit]) {
+        // A constraint is simplified by assignments at level 0.
+        self.vardata.cancel_trail_until(0);
+        self.retire_symmetries(lits);
+        lits.iter().for_each(|lit| {
+            while lit.var().val() >= self.vardata.num_var() as u32 {
+                self.new_var();
+            }
+        });
+        for lit in lits.iter() {
+            if self.elim.removed(lit.var()) {
+                self.restore(lit.var());
+            }
+        }
+        self.elim.pending = true;
+    }
+
+    /// Returns the number of learnt clauses.
+    pub fn num_learnts(&self) -> usize {
+        self.db.num_learnts()
+    }
+
+    /// Bump the activity of a learnt clause that is involved in a conflict and update its LBD.
+    fn bump_clause(&mut self, cref: CRef) {
+        if !self.db.get_mut(cref).learnt() {
+            return;
+        }
+        self.db.bump_activity(cref);
+        let mut clause = self.db.get_mut(cref);
+        clause.set_used(true);
+        if clause.lbd() > CORE_LBD {
+            let lbd = self.vardata.compute_lbd(clause.iter());
+            if lbd < clause.lbd() {
+                clause.set_lbd(lbd);
+            }
+        }
+    }
+
+    /// Returns the backtrack level and the LBD of the learnt clause.
+    fn analyze(&mut self, confl: Conflict) -> (u32, u32) {
         debug_assert!(!self.vardata.analyzer.seen.iter().any(|x| *x));
-        debug_assert!(confl != CRef::UNDEF);
         let decision_level = self.vardata.trail.decision_level();
         self.vardata.analyzer.learnt_clause.clear();
         self.vardata.analyzer.learnt_clause.push(Lit::default());
 
         let mut counter = 0;
-        {
-            let clause = self.db.get_mut(confl);
-            debug_assert!(!clause.deleted());
-            for &p in clause.iter() {
-                debug_assert!(self.vardata.eval(p) != LitBool::UnDef);
-                let var = p.var();
-                self.vardata.analyzer.seen[var] = true;
-                self.vardata.order_heap.bump_activity(var);
-                if self.vardata.level(var) < decision_level {
-                    self.vardata.analyzer.learnt_clause.push(p);
-                } else {
-                    counter += 1;
+        match confl {
+            Conflict::Clause(cref) => {
+                self.bump_clause(cref);
+                let clause = self.db.get_mut(cref);
+                debug_assert!(!clause.deleted());
+                for &p in clause.iter() {
+                    if self.vardata.analyze_lit(p, decision_level) {
+                        counter += 1;
+                    }
+                }
+            }
+            Conflict::Binary(p, q) => {
+                for p in [p, q] {
+                    if self.vardata.analyze_lit(p, decision_level) {
+                        counter += 1;
+                    }
                 }
             }
         }
         // Traverse an implication graph to 1-UIP(unique implication point)
         let first_uip = {
             let mut p = Lit::UNDEF;
-            for &lit in self.vardata.trail.stack.iter().rev() {
-                // skip a variable that isn't checked.
-                if !self.vardata.analyzer.seen[lit.var()] {
+            for idx in (0..self.vardata.trail.stack.len()).rev() {
+                let lit = self.vardata.trail.stack[idx];
+                // skip a variable that isn't checked or is at a lower level out of order.
+                if !self.vardata.analyzer.seen[lit.var()]
+                    || self.vardata.level(lit.var()) < decision_level
+                {
                     continue;
                 }
                 self.vardata.analyzer.seen[lit.var()] = false;
@@ -227,19 +451,31 @@ impl Solver {
                     p = lit;
                     break;
                 }
-                let reason = self.vardata.reason(lit.var());
-                let clause = self.db.get_mut(reason);
-                for &q in clause.iter().skip(1) {
-                    if self.vardata.analyzer.seen[q.var()] {
-                        continue;
+                match self.explain(lit.var()) {
+                    Reason::Clause(cref) => {
+                        self.bump_clause(cref);
+                        let clause = self.db.get_mut(cref);
+                        let len = clause.len();
+                        for &q in clause.iter().skip(1) {
+                            if self.vardata.analyze_lit(q, decision_level) {
+                                counter += 1;
+                            }
+                        }
+                        // The resolvent is the reason without `lit` if their sizes are equal.
+                        // It keeps two literals at the current level to be watched.
+                        let resolvent =
+                            self.vardata.analyzer.learnt_clause.len() - 1 + counter as usize;
+                        if self.otfs && counter > 1 && len > 3 && resolvent == len - 1 {
+                            self.strengthen_reason(cref);
+                        }
                     }
-                    self.vardata.analyzer.seen[q.var()] = true;
-                    self.vardata.order_heap.bump_activity(q.var());
-                    if self.vardata.level(q.var()) < decision_level {
-                        self.vardata.analyzer.learnt_clause.push(q);
-                    } else {
-                        counter += 1;
+                    Reason::Binary(q) => {
+                        if self.vardata.analyze_lit(q, decision_level) {
+                            counter += 1;
+                        }
                     }
+                    Reason::Undef => unreachable!("1-UIP is found before a decision"),
+                    Reason::Card(_) => unreachable!("a cardinality constraint is explained"),
                 }
             }
             p
@@ -251,7 +487,11 @@ impl Solver {
             .analyze_toclear
             .clone_from(&self.vardata.analyzer.learnt_clause);
 
-        self.vardata.minimize_conflict_clause(&mut self.db);
+        let size = self.vardata.analyzer.learnt_clause.len();
+        self.vardata
+            .minimize_conflict_clause(&mut self.db, self.shrink);
+        self.strengthen_by_binary();
+        self.stats.minimized_lits += (size - self.vardata.analyzer.learnt_clause.len()) as u64;
 
         let backtrack_level = if self.vardata.analyzer.learnt_clause.len() == 1 {
             0
@@ -277,11 +517,108 @@ impl Solver {
             max_level
         };
 
+        if self.vardata.branching.reason_side() {
+            // variables that are in reasons of the learnt clause but not in the conflict
+            for i in 0..self.vardata.analyzer.learnt_clause.len() {
+                let v = self.vardata.analyzer.learnt_clause[i].var();
+                match self.vardata.reason(v) {
+                    Reason::Clause(cref) => {
+                        for &q in self.db.get_mut(cref).iter().skip(1) {
+                            if !self.vardata.analyzer.seen[q.var()] {
+                                self.vardata.branching.bump_reason_side(q.var());
+                            }
+                        }
+                    }
+                    Reason::Binary(q) => {
+                        if !self.vardata.analyzer.seen[q.var()] {
+                            self.vardata.branching.bump_reason_side(q.var());
+                        }
+                    }
+                    Reason::Undef | Reason::Card(_) => {}
+                }
+            }
+        }
+
         // clear seen
         for lit in self.vardata.analyzer.analyze_toclear.iter() {
             self.vardata.analyzer.seen[lit.var()] = false;
         }
-        backtrack_level
+        let learnt_clause = std::mem::take(&mut self.vardata.analyzer.learnt_clause);
+        let lbd = self.vardata.compute_lbd(learnt_clause.iter());
+        self.vardata.analyzer.learnt_clause = learnt_clause;
+        (backtrack_level, lbd)
+    }
+
+    /// Collect assumptions that imply `p` into `failed_assumptions`.
+    /// `!p` is an assumption that is falsified by the current assignment.
+    fn analyze_final(&mut self, p: Lit) {
+        self.failed_assumptions.clear();
+        self.failed_assumptions.push(!p);
+        if self.vardata.trail.decision_level() == 0 || self.vardata.level(p.var()) == 0 {
+            return;
+        }
+        self.vardata.analyzer.seen[p.var()] = true;
+        let start = self.vardata.trail.stack_lim[0];
+        for idx in (start..self.vardata.trail.stack.len()).rev() {
+            let lit = self.vardata.trail.stack[idx];
+            let v = lit.var();
+            if !self.vardata.analyzer.seen[v] {
+                continue;
+            }
+            match self.explain(v) {
+                Reason::Undef => {
+                    // All decisions below the assumption levels are assumptions.
+                    debug_assert!(self.vardata.level(v) > 0);
+                    self.failed_assumptions.push(lit);
+                }
+                Reason::Clause(cref) => {
+                    let clause = self.db.get_mut(cref);
+                    for &q in clause.iter().skip(1) {
+                        if self.vardata.level(q.var()) > 0 {
+                            self.vardata.analyzer.seen[q.var()] = true;
+                        }
+                    }
+                }
+                Reason::Binary(q) => {
+                    if self.vardata.level(q.var()) > 0 {
+                        self.vardata.analyzer.seen[q.var()] = true;
+                    }
+                }
+                Reason::Card(_) => unreachable!("a cardinality constraint is explained"),
+            }
+            self.vardata.analyzer.seen[v] = false;
+        }
+        self.vardata.analyzer.seen[p.var()] = false;
+    }
+
+    /// Returns the next assumption that is not assigned yet.
+    /// An assumption that is already true opens a dummy decision level.
+    /// `Err(p)` means that an assumption `p` is already false.
+    fn pick_assumption(&mut self) -> Result<Lit, Lit> {
+        while (self.vardata.trail.decision_level() as usize) < self.assumptions.len() {
+            let p = self.assumptions[self.vardata.trail.decision_level() as usize];
+            match self.vardata.eval(p) {
+                LitBool::True => self.vardata.trail.new_decision_level(),
+                LitBool::False => return Err(p),
+                _ => return Ok(p),
+            }
+        }
+        Ok(Lit::UNDEF)
+    }
+
+    /// Returns the next decision literal by `branching` and `phases`.
+    /// `None` means that all variables are assigned.
+    fn pick_branch_lit(&mut self) -> Option<Lit> {
+        while let Some(v) = self.vardata.branching.pick() {
+            if self.vardata.define(v) || self.elim.eliminated(v) {
+                continue;
+            }
+            return Some(match self.vardata.phases.phase(v) {
+                LitBool::True => Lit::new(v.val(), true),
+                _ => Lit::new(v.val(), false),
+            });
+        }
+        None
     }
 
     fn interreupt(&mut self) -> bool {
@@ -297,82 +634,188 @@ impl Solver {
             _ => false,
         }
     }
-    fn search(&mut self, conflict_limit: u32) -> SatResult {
-        let mut conflict_cnt = 0;
+    fn search(&mut self) -> SatResult {
         loop {
-            let confl = self.propagate();
             // conflict
-            if confl != CRef::UNDEF {
-                if self.vardata.trail.decision_level() == 0 {
+            if let Some(mut confl) = self.propagate() {
+                let (conflict_level, single) = self.conflict_level(&mut confl);
+                if conflict_level == 0 {
                     self.result = SatResult::Unsat;
                     return SatResult::Unsat;
                 }
-                let backtrack_level = self.analyze(confl);
-                //eprintln!("{} {}", backtrack_level, self.vardata.analyzer.learnt_clause.len());
-                self.vardata.cancel_trail_until(backtrack_level);
+                if single {
+                    // The conflict is a unit clause at the level below the conflict level.
+                    self.vardata.cancel_trail_until(conflict_level - 1);
+                    let (p, reason) = match confl {
+                        Conflict::Clause(cref) => (self.db.get_mut(cref)[0], Reason::Clause(cref)),
+                        Conflict::Binary(p, q) => (p, Reason::Binary(q)),
+                    };
+                    let level = match confl {
+                        Conflict::Clause(cref) => {
+                            self.vardata.level(self.db.get_mut(cref)[1].var())
+                        }
+                        Conflict::Binary(_, q) => self.vardata.level(q.var()),
+                    };
+                    self.vardata.enqueue_at(p, level, reason);
+                    continue;
+                }
+                self.stats.conflicts += 1;
+                self.vardata.update_phases();
+                self.vardata.cancel_trail_until(conflict_level);
+                let (backtrack_level, lbd) = self.analyze(confl);
+                self.eager_subsume();
+                self.restart
+                    .on_conflict(lbd, self.vardata.trail.stack.len());
+                match self.chrono {
+                    Some(threshold) if conflict_level - backtrack_level > threshold => {
+                        self.stats.chrono_backtracks += 1;
+                        self.vardata.cancel_trail_until(conflict_level - 1);
+                    }
+                    _ => self.vardata.cancel_trail_until(backtrack_level),
+                }
 
-                if self.vardata.analyzer.learnt_clause.len() == 1 {
-                    let p = self.vardata.analyzer.learnt_clause[0];
-                    self.vardata.enqueue(p, CRef::UNDEF);
+                // The learnt clause is asserting at `backtrack_level`.
+                let learnt_clause = &self.vardata.analyzer.learnt_clause;
+                if learnt_clause.len() == 1 {
+                    let p = learnt_clause[0];
+                    self.vardata.enqueue_at(p, 0, Reason::Undef);
+                } else if learnt_clause.len() == 2 {
+                    let (p, q) = (learnt_clause[0], learnt_clause[1]);
+                    self.watches.watch_binary(p, q, true);
+                    self.vardata
+                        .enqueue_at(p, backtrack_level, Reason::Binary(q));
                 } else {
                     let cref = self.db.alloc(&self.vardata.analyzer.learnt_clause, true);
+                    self.db.get_mut(cref).set_lbd(lbd);
+                    self.db.bump_activity(cref);
                     self.watches
                         .watch(&self.vardata.analyzer.learnt_clause, cref);
-                    self.vardata
-                        .enqueue(self.vardata.analyzer.learnt_clause[0], cref);
+                    self.vardata.enqueue_at(
+                        self.vardata.analyzer.learnt_clause[0],
+                        backtrack_level,
+                        Reason::Clause(cref),
+                    );
                 }
-                self.vardata.order_heap.decay();
-                conflict_cnt += 1;
+                self.vardata.branching.decay();
+                self.db.decay_activity();
             } else {
                 // No conflict
-                loop {
-                    // restart
-                    if self.interreupt() || conflict_cnt >= conflict_limit {
-                        self.vardata.cancel_trail_until(0);
-                        return SatResult::Unknown;
-                    }
-
-                    if let Some(v) = self.vardata.order_heap.pop() {
-                        if self.vardata.define(v) {
-                            continue;
-                        }
-
-                        let lit = match self.vardata.polarity[v] {
-                            LitBool::True => Lit::new(v.val(), true),
-                            _ => Lit::new(v.val(), false),
-                        };
-                        self.vardata.trail.new_decision_level();
-                        self.vardata.enqueue(lit, CRef::UNDEF);
-                        break;
+                // restart
+                if self.interreupt() {
+                    self.vardata.cancel_trail_until(0);
+                    return SatResult::Unknown;
+                }
+                if self.restart.should_restart() {
+                    let level = if self.reuse_trail {
+                        // Assumptions are always decided again in the same order.
+                        let from_level = (self.assumptions.len() as u32)
+                            .min(self.vardata.trail.decision_level());
+                        let level = self.vardata.reuse_trail(from_level);
+                        self.stats.reused_levels += (level - from_level) as u64;
+                        level
                     } else {
-                        self.result = SatResult::Sat;
-                        return SatResult::Sat;
-                    }
+                        0
+                    };
+                    self.vardata.cancel_trail_until(level);
+                    return SatResult::Unknown;
                 }
+                if self.reduce.ready(self.stats.conflicts) {
+                    self.reduce.advance(self.stats.conflicts);
+                    self.reduce_db();
+                }
+
+                let lit = match self.pick_assumption() {
+                    Ok(lit) if lit.define() => lit,
+                    Ok(_) => match self.pick_branch_lit() {
+                        Some(lit) => lit,
+                        None => return SatResult::Sat,
+                    },
+                    Err(p) => {
+                        self.analyze_final(!p);
+                        return SatResult::Unsat;
+                    }
+                };
+                self.stats.decisions += 1;
+                self.vardata.trail.new_decision_level();
+                self.vardata.enqueue(lit, Reason::Undef);
             }
         }
     }
     pub fn solve(&mut self) -> SatResult {
+        self.solve_with_assumptions(&[])
+    }
+
+    /// Solve the formula under `assumptions` that are decided before any other variables.
+    /// If the result is `Unsat`, `failed_assumptions` has a subset of `assumptions`
+    /// that makes the formula unsatisfiable.
+    /// It is empty if the formula is unsatisfiable without any assumptions.
+    pub fn solve_with_assumptions(&mut self, assumptions: &[Lit]) -> SatResult {
+        self.failed_assumptions.clear();
+        self.retire_symmetries(assumptions);
         if self.result != SatResult::Unknown {
             return self.result;
         }
-        let mut result = SatResult::Unknown;
-        let mut restart_cnt = 0;
+        assumptions.iter().for_each(|lit| {
+            while lit.var().val() >= self.vardata.num_var() as u32 {
+                self.new_var();
+            }
+        });
+        for lit in assumptions.iter() {
+            if self.elim.removed(lit.var()) {
+                self.restore(lit.var());
+            }
+        }
+        self.assumptions = assumptions.to_vec();
+
+        let broken = self.break_symmetries();
+        if let Some(selector) = self.symmetry_selector() {
+            self.assumptions.push(selector);
+        }
+        let mut result = if broken && self.eliminate() && self.build_matrices() {
+            SatResult::Unknown
+        } else {
+            self.result = SatResult::Unsat;
+            SatResult::Unsat
+        };
         while result == SatResult::Unknown {
-            let conflict_limit = self.luby.seq(restart_cnt) as u32;
-            result = self.search(conflict_limit);
+            result = self.search();
             if self.interreupt() {
                 break;
             }
-            restart_cnt += 1;
+            if result == SatResult::Unknown {
+                self.restart.on_restart();
+                self.stats.restarts += 1;
+                self.vardata.phases.on_restart();
+                if self.vardata.phases.rephase_ready(self.stats.conflicts) {
+                    self.vardata.phases.rephase(self.stats.conflicts);
+                    self.stats.rephases += 1;
+                }
+                if !self.vivify_if_ready()
+                    || !self.probe_if_ready()
+                    || !self.subsume_if_ready()
+                    || !self.unhide_if_ready()
+                {
+                    self.result = SatResult::Unsat;
+                    result = SatResult::Unsat;
+                }
+            }
         }
 
+        if let Some(selector) = self.symmetry_selector() {
+            // The lex-leader constraints keep a model of the symmetric formula and assumptions.
+            self.failed_assumptions.retain(|&lit| lit != selector);
+        }
         if result == SatResult::Sat {
             self.models.resize(self.vardata.num_var(), LitBool::UnDef);
             for v in (0..self.vardata.num_var()).map(var::Var::from_idx) {
                 self.models[v.val() as usize] = self.vardata.lbool(v);
             }
+            self.elim.extend_model(&mut self.models);
         }
+        // Get ready for the next call.
+        self.vardata.cancel_trail_until(0);
+        self.assumptions.clear();
+        self.interreupt = false;
         result
     }
 }
@@ -1,21 +1,41 @@
-use crate::{clause::alloc::CRef, collections::idxvec::VarVec, types::lit::Lit};
+use crate::{collections::idxvec::VarVec, types::lit::Lit};
+
 /// `Analyzer` has a bunch of data that is used in `analyze`.
 pub struct Analyzer {
     pub seen: VarVec<bool>,
-    pub ccmin_stack: Vec<CRef>,
+    /// A literal is known not to be redundant in the current minimization.
+    pub poison: VarVec<bool>,
+    /// a temporary mark that is used by shrinking and binary strengthening
+    pub mark: VarVec<bool>,
+    /// the path of the DFS in `redundant`: a literal and the index of its next antecedent
+    pub ccmin_stack: Vec<(Lit, usize)>,
+    /// literals whose `seen` or `poison` are set by the minimization
     pub ccmin_clear: Vec<Lit>,
     pub analyze_toclear: Vec<Lit>,
     pub learnt_clause: Vec<Lit>,
+    /// `lbd_stamp[level]` is `lbd_counter` if the level is already counted.
+    pub lbd_stamp: Vec<u32>,
+    pub lbd_counter: u32,
 }
 
 impl Analyzer {
     pub fn new() -> Analyzer {
         Analyzer {
             seen: VarVec::new(),
+            poison: VarVec::new(),
+            mark: VarVec::new(),
             ccmin_stack: Vec::new(),
             ccmin_clear: Vec::new(),
             analyze_toclear: Vec::new(),
             learnt_clause: Vec::new(),
+            lbd_stamp: Vec::new(),
+            lbd_counter: 0,
         }
     }
+
+    pub fn new_var(&mut self) {
+        self.seen.push(false);
+        self.poison.push(false);
+        self.mark.push(false);
+    }
 }
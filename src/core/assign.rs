@@ -8,6 +8,7 @@ pub struct AssignTrail {
     /// e.g. For `stack`,
     /// 0..stack_lim[0] is all assignments at 0 level
     /// stack_lim[0]..stack_lim[1] is all assignments  at 1 level
+    /// Chronological backtracking may leave assignments at lower levels after a separator.
     pub stack_lim: Vec<usize>,
     /// Head of `stack`
     pub peek_head: usize,
@@ -44,6 +45,7 @@ impl AssignTrail {
     }
 
     /// Pop the `peek-head` `stack`
+    #[allow(dead_code)]
     pub fn pop(&mut self) -> Option<Lit> {
         let res = if self.peekable() {
             Some(self.peek())
@@ -65,6 +67,7 @@ impl AssignTrail {
     }
 
     /// Returns the number of assignment
+    #[allow(dead_code)]
     pub fn num_assign(&self) -> usize {
         self.stack.len()
     }
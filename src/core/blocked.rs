@@ -0,0 +1,187 @@
+use crate::types::lit::Lit;
+
+use super::{
+    elim::{Occurs, ELIM_ROUNDS},
+    Solver,
+};
+
+/// Covered literal addition stops before a clause grows longer than this.
+const COVERED_SIZE_LIMIT: usize = 64;
+/// The maximum number of literals visited by blocked clause elimination.
+const BLOCKED_EFFORT: u64 = 10_000_000;
+
+/// Returns `lits` with `first` moved to the front.
+fn with_first(lits: &[Lit], first: Lit) -> Vec<Lit> {
+    let mut lits = lits.to_vec();
+    let idx = lits
+        .iter()
+        .position(|&lit| lit == first)
+        .expect("no witness");
+    lits.swap(0, idx);
+    lits
+}
+
+impl Occurs {
+    /// Returns the removal steps of clause `c` if it is blocked, or covered if `covered` is true.
+    /// Each step is a clause with its witness first: the clause extended by covered literals
+    /// before each covered literal addition and lastly the extended clause that is blocked.
+    /// Literals of `frozen` variables are never witnesses.
+    fn blocked(
+        &mut self,
+        c: usize,
+        frozen: &[bool],
+        covered: bool,
+        ticks: &mut u64,
+    ) -> Option<Vec<Vec<Lit>>> {
+        let mut extended = self.clauses[c].clone();
+        for &lit in extended.iter() {
+            self.mark[lit] = true;
+        }
+        let mut steps = Vec::new();
+        let mut blocking = None;
+        let mut progress = true;
+        while blocking.is_none() && progress {
+            progress = false;
+            let mut i = 0;
+            while blocking.is_none() && i < extended.len() {
+                let lit = extended[i];
+                i += 1;
+                if frozen[lit.var().val() as usize] {
+                    continue;
+                }
+                // the literals in every resolvent on `lit` that isn't a tautology
+                let mut intersection: Option<Vec<Lit>> = None;
+                for &d in self.occs(!lit).to_vec().iter() {
+                    let clause = &self.clauses[d];
+                    *ticks += clause.len() as u64;
+                    if clause
+                        .iter()
+                        .any(|&other| other != !lit && self.mark[!other])
+                    {
+                        continue;
+                    }
+                    if let Some(lits) = intersection.as_mut() {
+                        lits.retain(|other| clause.contains(other));
+                    } else {
+                        intersection = Some(
+                            clause
+                                .iter()
+                                .copied()
+                                .filter(|&other| other != !lit && !self.mark[other])
+                                .collect(),
+                        );
+                    }
+                    if !covered || intersection.as_ref().is_some_and(|lits| lits.is_empty()) {
+                        break;
+                    }
+                }
+                match intersection {
+                    None => blocking = Some(lit),
+                    Some(lits)
+                        if covered
+                            && !lits.is_empty()
+                            && extended.len() + lits.len() <= COVERED_SIZE_LIMIT =>
+                    {
+                        steps.push(with_first(&extended, lit));
+                        for &other in lits.iter() {
+                            self.mark[other] = true;
+                        }
+                        extended.extend(lits);
+                        progress = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for &lit in extended.iter() {
+            self.mark[lit] = false;
+        }
+        blocking.map(|lit| {
+            steps.push(with_first(&extended, lit));
+            steps
+        })
+    }
+}
+
+impl Solver {
+    /// Remove blocked clauses, and covered clauses if enabled, from `occurs`.
+    /// Their removal steps are pushed to the stack of elimination for `extend_model`.
+    pub(super) fn eliminate_blocked(&mut self, occurs: &mut Occurs, frozen: &[bool]) {
+        let covered = self.elim.covered;
+        let mut ticks = 0;
+        for _ in 0..ELIM_ROUNDS {
+            let mut progress = false;
+            for c in 0..occurs.clauses.len() {
+                if ticks > BLOCKED_EFFORT {
+                    break;
+                }
+                if occurs.removed[c] {
+                    continue;
+                }
+                if let Some(steps) = occurs.blocked(c, frozen, covered, &mut ticks) {
+                    occurs.remove(c);
+                    if steps.len() > 1 {
+                        self.stats.covered_clauses += 1;
+                    } else {
+                        self.stats.blocked_clauses += 1;
+                    }
+                    for (i, step) in steps.into_iter().enumerate() {
+                        self.elim.push(step, i > 0);
+                    }
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::elim::Occurs, types::lit::Lit};
+
+    fn occurs(clauses: &[Vec<Lit>]) -> Occurs {
+        let mut occurs = Occurs::new(4);
+        for clause in clauses.iter() {
+            occurs.add(clause.clone());
+        }
+        occurs
+    }
+
+    #[test]
+    fn test_blocked() {
+        let lits: Vec<Lit> = (0..4).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d) = (lits[0], lits[1], lits[2], lits[3]);
+        let clauses = [vec![a, b], vec![!a, c], vec![!a, c, d], vec![!b, !c]];
+        let mut ticks = 0;
+
+        // `a v b` isn't blocked, but `c` is covered on `a` and `a v b v c` is blocked on `b`.
+        let frozen = [false; 4];
+        assert_eq!(
+            occurs(&clauses).blocked(0, &frozen, false, &mut ticks),
+            None
+        );
+        assert_eq!(
+            occurs(&clauses).blocked(0, &frozen, true, &mut ticks),
+            Some(vec![vec![a, b], vec![b, a, c]])
+        );
+
+        // A frozen variable is never a witness.
+        let frozen = [false, true, false, false];
+        assert_eq!(
+            occurs(&clauses).blocked(0, &frozen, true, &mut ticks),
+            Some(vec![vec![a, b], vec![c, b, a]])
+        );
+
+        // `!b v !c` is blocked on `!b` after `a v b` is removed.
+        let mut occurs = occurs(&clauses);
+        occurs.remove(0);
+        assert_eq!(occurs.blocked(3, &frozen, false, &mut ticks), None);
+        assert_eq!(
+            occurs.blocked(3, &[false; 4], false, &mut ticks),
+            Some(vec![vec![!b, !c]])
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use crate::{
+    collections::{heap::Heap, idxvec::VarVec},
+    types::var::Var,
+};
+
+use super::vmtf::Vmtf;
+
+/// `Branching` decides which variable is assigned next in `Solver::search`.
+pub trait Branching {
+    /// Called when a new variable is added.
+    fn new_var(&mut self, v: Var);
+    /// Called when a variable is assigned by a decision or a propagation.
+    fn on_assign(&mut self, _v: Var) {}
+    /// Called when a variable is unassigned by backtracking.
+    fn on_unassign(&mut self, v: Var);
+    /// Called for each variable that is involved in conflict analysis.
+    fn bump(&mut self, v: Var);
+    /// Returns true if `bump_reason_side` should be called.
+    fn reason_side(&self) -> bool {
+        false
+    }
+    /// Called for each variable that is in reasons of a learnt clause but not in conflict analysis.
+    fn bump_reason_side(&mut self, _v: Var) {}
+    /// Called after each conflict.
+    fn decay(&mut self);
+    /// Returns the next candidate of a decision variable.
+    /// A returned variable may be already assigned.
+    fn pick(&mut self) -> Option<Var>;
+    /// Returns the next decision variable that is not `assigned` without deciding it.
+    fn peek(&mut self, assigned: &dyn Fn(Var) -> bool) -> Option<Var>;
+    /// Returns true if `a` is decided before `b`.
+    fn prefer(&self, a: Var, b: Var) -> bool;
+}
+
+/// `BranchingStrategy` selects a `Branching` heuristic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BranchingStrategy {
+    /// Variable State Independent Decaying Sum
+    #[default]
+    Vsids,
+    /// Learning-Rate Branching
+    Lrb,
+    /// Conflict-History-Based Branching
+    Chb,
+    /// Variable Move To Front
+    Vmtf,
+}
+
+impl BranchingStrategy {
+    pub fn build(&self) -> Box<dyn Branching> {
+        match self {
+            BranchingStrategy::Vsids => Box::new(Vsids::default()),
+            BranchingStrategy::Lrb => Box::new(Lrb::default()),
+            BranchingStrategy::Chb => Box::new(Chb::default()),
+            BranchingStrategy::Vmtf => Box::new(Vmtf::default()),
+        }
+    }
+}
+
+impl std::str::FromStr for BranchingStrategy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "vsids" => Ok(BranchingStrategy::Vsids),
+            "lrb" => Ok(BranchingStrategy::Lrb),
+            "chb" => Ok(BranchingStrategy::Chb),
+            "vmtf" => Ok(BranchingStrategy::Vmtf),
+            _ => Err(format!("unknown branching heuristic: {}", s)),
+        }
+    }
+}
+
+/// `Vsids` bumps variables in conflicts and decays all activities by increasing the bump.
+#[derive(Default)]
+pub struct Vsids {
+    order_heap: Heap,
+}
+
+impl Branching for Vsids {
+    fn new_var(&mut self, v: Var) {
+        self.order_heap.push(v);
+    }
+    fn on_unassign(&mut self, v: Var) {
+        self.order_heap.push(v);
+    }
+    fn bump(&mut self, v: Var) {
+        self.order_heap.bump_activity(v);
+    }
+    fn decay(&mut self) {
+        self.order_heap.decay();
+    }
+    fn pick(&mut self) -> Option<Var> {
+        self.order_heap.pop()
+    }
+    fn peek(&mut self, assigned: &dyn Fn(Var) -> bool) -> Option<Var> {
+        self.order_heap.top_unassigned(assigned)
+    }
+    fn prefer(&self, a: Var, b: Var) -> bool {
+        self.order_heap.gt(a, b)
+    }
+}
+
+/// The step size of ERWA(exponential recency weighted average) that LRB and CHB use.
+struct StepSize {
+    alpha: f64,
+    min: f64,
+    dec: f64,
+}
+
+impl Default for StepSize {
+    fn default() -> Self {
+        StepSize {
+            alpha: 0.4,
+            min: 0.06,
+            dec: 1e-6,
+        }
+    }
+}
+
+impl StepSize {
+    fn decay(&mut self) {
+        if self.alpha > self.min {
+            self.alpha -= self.dec;
+        }
+    }
+}
+
+/// `Lrb` rewards a variable by the rate of conflicts it participates in while it is assigned.
+/// It has reason side rate and anti-exploration extensions.
+#[derive(Default)]
+pub struct Lrb {
+    order_heap: Heap,
+    step: StepSize,
+    /// the number of conflicts
+    conflicts: u64,
+    /// `conflicts` when a variable is assigned
+    assigned: VarVec<u64>,
+    /// the number of conflicts a variable participates in since it is assigned
+    participated: VarVec<u64>,
+    /// the number of conflicts a variable is in reasons of a learnt clause since it is assigned
+    reasoned: VarVec<u64>,
+    /// `conflicts` when a variable is unassigned
+    canceled: VarVec<u64>,
+}
+
+impl Branching for Lrb {
+    fn new_var(&mut self, v: Var) {
+        self.assigned.push(0);
+        self.participated.push(0);
+        self.reasoned.push(0);
+        self.canceled.push(0);
+        self.order_heap.push(v);
+    }
+    fn on_assign(&mut self, v: Var) {
+        self.assigned[v] = self.conflicts;
+        self.participated[v] = 0;
+        self.reasoned[v] = 0;
+    }
+    fn on_unassign(&mut self, v: Var) {
+        let interval = self.conflicts - self.assigned[v];
+        if interval > 0 {
+            let reward = (self.participated[v] + self.reasoned[v]) as f64 / interval as f64;
+            let q = self.order_heap.activity[v];
+            let alpha = self.step.alpha;
+            self.order_heap
+                .set_activity(v, (1.0 - alpha) * q + alpha * reward);
+        }
+        self.canceled[v] = self.conflicts;
+        self.order_heap.push(v);
+    }
+    fn bump(&mut self, v: Var) {
+        self.participated[v] += 1;
+    }
+    fn reason_side(&self) -> bool {
+        true
+    }
+    fn bump_reason_side(&mut self, v: Var) {
+        self.reasoned[v] += 1;
+    }
+    fn decay(&mut self) {
+        self.conflicts += 1;
+        self.step.decay();
+    }
+    fn pick(&mut self) -> Option<Var> {
+        // anti-exploration: decay variables that stay unassigned for a long time.
+        while let Some(v) = self.order_heap.top() {
+            let age = self.conflicts - self.canceled[v];
+            if age == 0 {
+                break;
+            }
+            let q = self.order_heap.activity[v];
+            self.order_heap
+                .set_activity(v, q * 0.95f64.powi(age as i32));
+            self.canceled[v] = self.conflicts;
+        }
+        self.order_heap.pop()
+    }
+    fn peek(&mut self, assigned: &dyn Fn(Var) -> bool) -> Option<Var> {
+        self.order_heap.top_unassigned(assigned)
+    }
+    fn prefer(&self, a: Var, b: Var) -> bool {
+        self.order_heap.gt(a, b)
+    }
+}
+
+/// `Chb` rewards assigned variables by how recently they were involved in a conflict.
+#[derive(Default)]
+pub struct Chb {
+    order_heap: Heap,
+    step: StepSize,
+    /// the number of conflicts
+    conflicts: u64,
+    /// the number of conflicts when a variable was involved in a conflict the last time
+    last_conflict: VarVec<u64>,
+    /// variables that are assigned since the last reward
+    assigned: Vec<Var>,
+}
+
+impl Chb {
+    /// Reward variables that are assigned since the last reward.
+    fn reward(&mut self, multiplier: f64) {
+        let alpha = self.step.alpha;
+        for &v in self.assigned.iter() {
+            let age = self.conflicts - self.last_conflict[v] + 1;
+            let reward = multiplier / age as f64;
+            let q = self.order_heap.activity[v];
+            self.order_heap
+                .set_activity(v, (1.0 - alpha) * q + alpha * reward);
+        }
+        self.assigned.clear();
+    }
+}
+
+impl Branching for Chb {
+    fn new_var(&mut self, v: Var) {
+        self.last_conflict.push(0);
+        self.order_heap.push(v);
+    }
+    fn on_assign(&mut self, v: Var) {
+        self.assigned.push(v);
+    }
+    fn on_unassign(&mut self, v: Var) {
+        self.order_heap.push(v);
+    }
+    fn bump(&mut self, v: Var) {
+        // `conflicts` is incremented after the analysis of the current conflict.
+        self.last_conflict[v] = self.conflicts + 1;
+    }
+    fn decay(&mut self) {
+        self.conflicts += 1;
+        // A propagation that leads to a conflict gets a full reward.
+        self.reward(1.0);
+        self.step.decay();
+    }
+    fn pick(&mut self) -> Option<Var> {
+        // A propagation without any conflicts gets a smaller reward.
+        self.reward(0.9);
+        self.order_heap.pop()
+    }
+    fn peek(&mut self, assigned: &dyn Fn(Var) -> bool) -> Option<Var> {
+        self.order_heap.top_unassigned(assigned)
+    }
+    fn prefer(&self, a: Var, b: Var) -> bool {
+        self.order_heap.gt(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick_unassigned(branching: &mut dyn Branching) -> Var {
+        branching.pick().expect("no variable")
+    }
+
+    #[test]
+    fn test_vsids() {
+        let mut vsids = BranchingStrategy::Vsids.build();
+        for i in 0..3 {
+            vsids.new_var(Var(i));
+        }
+        vsids.bump(Var(2));
+        vsids.decay();
+        assert_eq!(pick_unassigned(vsids.as_mut()), Var(2));
+        vsids.on_unassign(Var(2));
+        assert_eq!(pick_unassigned(vsids.as_mut()), Var(2));
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut vsids = BranchingStrategy::Vsids.build();
+        for i in 0..3 {
+            vsids.new_var(Var(i));
+        }
+        vsids.bump(Var(1));
+        vsids.bump(Var(1));
+        vsids.bump(Var(2));
+        assert!(vsids.prefer(Var(1), Var(2)));
+        assert_eq!(vsids.peek(&|v| v == Var(1)), Some(Var(2)));
+        // `peek` doesn't decide a variable.
+        assert_eq!(vsids.peek(&|_| false), Some(Var(2)));
+        assert_eq!(pick_unassigned(vsids.as_mut()), Var(2));
+    }
+
+    #[test]
+    fn test_lrb() {
+        let mut lrb = Lrb::default();
+        for i in 0..3 {
+            lrb.new_var(Var(i));
+        }
+        // Var(1) participates in all conflicts while it is assigned.
+        lrb.on_assign(Var(0));
+        lrb.on_assign(Var(1));
+        for _ in 0..4 {
+            lrb.bump(Var(1));
+            lrb.decay();
+        }
+        lrb.on_unassign(Var(1));
+        lrb.on_unassign(Var(0));
+        assert_eq!(lrb.pick(), Some(Var(1)));
+    }
+
+    #[test]
+    fn test_chb() {
+        let mut chb = Chb::default();
+        for i in 0..3 {
+            chb.new_var(Var(i));
+        }
+        chb.on_assign(Var(0));
+        chb.on_assign(Var(2));
+        chb.bump(Var(2));
+        chb.decay();
+        chb.on_unassign(Var(0));
+        chb.on_unassign(Var(2));
+        assert_eq!(chb.pick(), Some(Var(2)));
+        assert!(chb.order_heap.activity[Var(2)] > chb.order_heap.activity[Var(0)]);
+    }
+
+    #[test]
+    fn test_branching_strategy_from_str() {
+        assert_eq!("lrb".parse(), Ok(BranchingStrategy::Lrb));
+        assert!("foo".parse::<BranchingStrategy>().is_err());
+    }
+}
@@ -0,0 +1,777 @@
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+};
+
+use crate::{
+    collections::idxvec::LitVec,
+    types::{bool::LitBool, lit::Lit, var::Var},
+};
+
+use super::{data::Reason, elim::Occurs, Conflict, SatResult, Solver};
+
+/// Cliques of binary clauses of at least this many literals are recovered as at-most-one constraints.
+const CARD_DETECT_MIN_SIZE: usize = 3;
+/// At-most-k constraints of at most this `k` are recovered from the clauses of all `k + 1` subsets.
+const CARD_DETECT_MAX_K: usize = 3;
+/// The recovery of cardinality constraints visits at most this many literals.
+const CARD_DETECT_EFFORT: u64 = 10_000_000;
+
+/// `Card` is a constraint that the sum of the weights of true `lits` is at most `k`.
+/// Literals are in the descending order of their weights, which are all 1 in at-most-k constraints.
+struct Card {
+    lits: Vec<Lit>,
+    weights: Vec<u64>,
+    k: u64,
+}
+
+/// `Cards` keeps cardinality and pseudo-Boolean constraints with the sums of the weights
+/// of their true literals. The sums are decreased lazily when `propagate` starts after backtracking.
+pub struct Cards {
+    cards: Vec<Card>,
+    /// the sum of the weights of true literals of each constraint that are counted
+    counts: Vec<u64>,
+    /// the constraints of each literal with its weights
+    occs: LitVec<Vec<(u32, u64)>>,
+    /// counted literals with their indices in the trail
+    counted: Vec<(usize, Lit)>,
+    /// the index of the next literal to count in the trail
+    head: usize,
+    /// recover constraints from clauses before search
+    pub(super) detect: bool,
+}
+
+impl Cards {
+    pub fn new(detect: bool) -> Cards {
+        Cards {
+            cards: Vec::new(),
+            counts: Vec::new(),
+            occs: LitVec::new(),
+            counted: Vec::new(),
+            head: 0,
+            detect,
+        }
+    }
+
+    pub fn new_var(&mut self) {
+        self.occs.push(Vec::new());
+        self.occs.push(Vec::new());
+    }
+
+    /// Add an at-most-k constraint of unassigned literals.
+    fn add(&mut self, lits: Vec<Lit>, k: u32) {
+        let weights = vec![1; lits.len()];
+        self.add_weighted(lits, weights, k as u64);
+    }
+
+    /// Add a constraint of unassigned literals with their weights and returns its index.
+    fn add_weighted(&mut self, lits: Vec<Lit>, weights: Vec<u64>, k: u64) -> usize {
+        let idx = self.cards.len();
+        let mut terms: Vec<(u64, Lit)> = weights.into_iter().zip(lits).collect();
+        terms.sort_by_key(|&(weight, _)| std::cmp::Reverse(weight));
+        for &(weight, lit) in terms.iter() {
+            self.occs[lit].push((idx as u32, weight));
+        }
+        self.cards.push(Card {
+            lits: terms.iter().map(|&(_, lit)| lit).collect(),
+            weights: terms.iter().map(|&(weight, _)| weight).collect(),
+            k,
+        });
+        self.counts.push(0);
+        idx
+    }
+
+    /// Decrease the bound of a constraint by `delta`. Returns false if it becomes negative.
+    /// All literals are counted again, so the constraint propagates at level 0.
+    fn tighten(&mut self, c: usize, delta: u64) -> bool {
+        self.uncount(0);
+        match self.cards[c].k.checked_sub(delta) {
+            Some(k) => {
+                self.cards[c].k = k;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn vars(&self) -> impl Iterator<Item = Var> + '_ {
+        self.cards
+            .iter()
+            .flat_map(|card| card.lits.iter().map(|lit| lit.var()))
+    }
+
+    /// Remove the literals of an unassigned variable `v` from all constraints as if they were false.
+    pub(super) fn remove_var(&mut self, v: Var) {
+        for lit in [Lit::new(v.val(), true), Lit::new(v.val(), false)].iter() {
+            for (c, _) in std::mem::take(&mut self.occs[*lit]) {
+                let card = &mut self.cards[c as usize];
+                let pos = card.lits.iter().position(|l| l == lit).expect("no literal");
+                card.lits.remove(pos);
+                card.weights.remove(pos);
+            }
+        }
+    }
+
+    /// Forget the literals counted at `head` or later in the trail,
+    /// which are unassigned or will be propagated again.
+    pub(super) fn uncount(&mut self, head: usize) {
+        while let Some(&(idx, lit)) = self.counted.last() {
+            if idx < head {
+                break;
+            }
+            self.counted.pop();
+            for &(c, weight) in self.occs[lit].iter() {
+                debug_assert!(self.counts[c as usize] >= weight);
+                self.counts[c as usize] -= weight;
+            }
+        }
+        self.head = self.head.min(head);
+    }
+
+    /// Count a true literal `lit` at `idx` in the trail.
+    /// Returns false if it isn't in any constraint.
+    fn count(&mut self, idx: usize, lit: Lit) -> bool {
+        if self.occs[lit].is_empty() {
+            return false;
+        }
+        self.counted.push((idx, lit));
+        for &(c, weight) in self.occs[lit].iter() {
+            self.counts[c as usize] += weight;
+        }
+        true
+    }
+}
+
+/// Returns all subsets of `r` indices out of `n` in the lexicographic order.
+fn combinations(n: usize, r: usize) -> Vec<Vec<usize>> {
+    let mut subsets = Vec::new();
+    if r > n {
+        return subsets;
+    }
+    let mut idx: Vec<usize> = (0..r).collect();
+    loop {
+        subsets.push(idx.clone());
+        // the last index that can be advanced
+        let mut i = r;
+        loop {
+            if i == 0 {
+                return subsets;
+            }
+            i -= 1;
+            if idx[i] < n - r + i {
+                break;
+            }
+        }
+        idx[i] += 1;
+        for j in i + 1..r {
+            idx[j] = idx[j - 1] + 1;
+        }
+    }
+}
+
+impl Solver {
+    /// Add a cardinality constraint that at most `k` of `lits` are true.
+    /// A duplicated literal is counted once. Constraints that are equivalent to clauses
+    /// are added as clauses, and the others are propagated natively.
+    pub fn add_at_most(&mut self, lits: &[Lit], k: u32) {
+        if self.result == SatResult::Unsat {
+            return;
+        }
+        self.prepare_constraint(lits);
+        self.cards.uncount(self.vardata.trail.peek_head);
+
+        let mut lits = lits.to_vec();
+        lits.sort();
+        lits.dedup();
+        let mut k = k as usize;
+        let mut unassigned = Vec::with_capacity(lits.len());
+        let mut i = 0;
+        while i < lits.len() {
+            // Exactly one of `x` and `!x` is true, as is a literal that is true at level 0.
+            let forced = if i + 1 < lits.len() && lits[i + 1] == !lits[i] {
+                i += 1;
+                true
+            } else {
+                match self.vardata.eval(lits[i]) {
+                    LitBool::True => true,
+                    LitBool::False => false,
+                    _ => {
+                        unassigned.push(lits[i]);
+                        false
+                    }
+                }
+            };
+            if forced {
+                if k == 0 {
+                    self.result = SatResult::Unsat;
+                    return;
+                }
+                k -= 1;
+            }
+            i += 1;
+        }
+
+        if k >= unassigned.len() {
+            return;
+        }
+        if k == 0 {
+            for &lit in unassigned.iter() {
+                self.add_clause(&[!lit]);
+            }
+        } else if k + 1 == unassigned.len() {
+            let clause: Vec<Lit> = unassigned.iter().map(|&lit| !lit).collect();
+            self.add_clause(&clause);
+        } else {
+            self.cards.add(unassigned, k as u32);
+        }
+    }
+
+    /// Add a pseudo-Boolean constraint that the sum of the coefficients of true literals
+    /// in `terms` is at least `degree`. Coefficients can be negative.
+    /// Constraints whose coefficients are equal are added as at-most-k constraints.
+    pub fn add_pb(&mut self, terms: &[(i64, Lit)], degree: i64) {
+        self.add_pb_constraint(terms, degree);
+    }
+
+    /// Add a pseudo-Boolean constraint and returns its index if it is propagated natively
+    /// as the constraint that the sum of the coefficients of false literals is at most the slack.
+    pub(super) fn add_pb_constraint(&mut self, terms: &[(i64, Lit)], degree: i64) -> Option<usize> {
+        if self.result == SatResult::Unsat {
+            return None;
+        }
+        let lits: Vec<Lit> = terms.iter().map(|&(_, lit)| lit).collect();
+        self.prepare_constraint(&lits);
+        self.cards.uncount(self.vardata.trail.peek_head);
+
+        // `-a x` is `a !x - a`, so every coefficient is positive.
+        let mut rest = degree as i128;
+        let mut normalized: Vec<(Lit, i128)> = terms
+            .iter()
+            .map(|&(a, lit)| {
+                if a < 0 {
+                    rest -= a as i128;
+                    (!lit, -(a as i128))
+                } else {
+                    (lit, a as i128)
+                }
+            })
+            .collect();
+        normalized.sort_by_key(|&(lit, _)| lit);
+        let mut merged: Vec<(Lit, i128)> = Vec::with_capacity(normalized.len());
+        for (lit, a) in normalized {
+            match merged.last_mut() {
+                Some((last, b)) if *last == lit => *b += a,
+                _ => merged.push((lit, a)),
+            }
+        }
+        // Exactly one of `x` and `!x` is true.
+        for i in 1..merged.len() {
+            if merged[i].0 == !merged[i - 1].0 {
+                let common = merged[i].1.min(merged[i - 1].1);
+                rest -= common;
+                merged[i].1 -= common;
+                merged[i - 1].1 -= common;
+            }
+        }
+        let mut unassigned = Vec::with_capacity(merged.len());
+        for (lit, a) in merged {
+            match self.vardata.eval(lit) {
+                LitBool::True => rest -= a,
+                LitBool::False => {}
+                _ if a == 0 => {}
+                _ => unassigned.push((lit, a)),
+            }
+        }
+        if rest <= 0 {
+            return None;
+        }
+        let total: i128 = unassigned.iter().map(|&(_, a)| a).sum();
+        if total < rest {
+            self.result = SatResult::Unsat;
+            return None;
+        }
+        // A literal whose coefficient exceeds the slack is true.
+        let slack = total - rest;
+        let forced: Vec<Lit> = unassigned
+            .iter()
+            .filter(|&&(_, a)| a > slack)
+            .map(|&(lit, _)| lit)
+            .collect();
+        if !forced.is_empty() {
+            for lit in forced {
+                self.add_clause(&[lit]);
+            }
+            return self.add_pb_constraint(terms, degree);
+        }
+
+        let lits: Vec<Lit> = unassigned.iter().map(|&(lit, _)| !lit).collect();
+        let a = unassigned[0].1;
+        if unassigned.iter().all(|&(_, b)| b == a) {
+            let k = (slack / a).min(lits.len() as i128);
+            self.add_at_most(&lits, k as u32);
+            return None;
+        }
+        let weights = unassigned
+            .iter()
+            .map(|&(_, a)| u64::try_from(a).expect("too large coefficient"))
+            .collect();
+        let slack = u64::try_from(slack).expect("too large coefficients");
+        Some(self.cards.add_weighted(lits, weights, slack))
+    }
+
+    /// Decrease the bound of a native constraint by `delta` at level 0,
+    /// which implies the literals whose weights exceed the new bound false.
+    pub(super) fn tighten_card(&mut self, c: usize, delta: u64) {
+        self.vardata.cancel_trail_until(0);
+        if !self.cards.tighten(c, delta) || self.propagate().is_some() {
+            self.result = SatResult::Unsat;
+            return;
+        }
+        let card = &self.cards.cards[c];
+        if self.cards.counts[c] + card.weights[0] > card.k
+            && self.propagate_card(c)
+            && self.propagate().is_some()
+        {
+            self.result = SatResult::Unsat;
+        }
+    }
+
+    /// Replace at-most-one constraints encoded by cliques of binary clauses
+    /// and at-most-k constraints encoded by the clauses of all `k + 1` subsets in `occurs`
+    /// with native cardinality constraints.
+    pub(super) fn detect_cards(&mut self, occurs: &mut Occurs) {
+        if !self.cards.detect {
+            return;
+        }
+        let mut ticks = 0;
+        self.detect_at_most_one(occurs, &mut ticks);
+        for k in 2..=CARD_DETECT_MAX_K {
+            self.detect_at_most_k(occurs, k, &mut ticks);
+        }
+    }
+
+    /// Replace cliques of binary clauses `!a v !b` by at-most-one constraints greedily
+    /// from literals of the most binary clauses.
+    fn detect_at_most_one(&mut self, occurs: &mut Occurs, ticks: &mut u64) {
+        let num_lit = 2 * self.vardata.num_var();
+        let key = |a: Lit, b: Lit| if a < b { (a, b) } else { (b, a) };
+        // binary clauses that aren't replaced yet by the pairs of literals that they forbid
+        let mut pairs: HashMap<(Lit, Lit), usize> = HashMap::new();
+        let mut neighbors: LitVec<Vec<Lit>> = LitVec::new();
+        for _ in 0..num_lit {
+            neighbors.push(Vec::new());
+        }
+        for (idx, clause) in occurs.clauses.iter().enumerate() {
+            if occurs.removed[idx] || clause.len() != 2 {
+                continue;
+            }
+            let (a, b) = (!clause[0], !clause[1]);
+            if pairs.insert(key(a, b), idx).is_none() {
+                neighbors[a].push(b);
+                neighbors[b].push(a);
+            }
+        }
+        let mut order: Vec<Lit> = (0..num_lit)
+            .map(Lit::from_idx)
+            .filter(|&a| neighbors[a].len() + 1 >= CARD_DETECT_MIN_SIZE)
+            .collect();
+        order.sort_by_key(|&a| std::cmp::Reverse(neighbors[a].len()));
+        for a in order {
+            if *ticks > CARD_DETECT_EFFORT {
+                break;
+            }
+            let mut candidates: Vec<Lit> = neighbors[a]
+                .iter()
+                .copied()
+                .filter(|&b| pairs.contains_key(&key(a, b)))
+                .collect();
+            if candidates.len() + 1 < CARD_DETECT_MIN_SIZE {
+                continue;
+            }
+            candidates.sort_by_key(|&b| std::cmp::Reverse(neighbors[b].len()));
+            let mut clique = vec![a];
+            for b in candidates {
+                *ticks += clique.len() as u64;
+                if clique.iter().all(|&c| pairs.contains_key(&key(b, c))) {
+                    clique.push(b);
+                }
+            }
+            if clique.len() < CARD_DETECT_MIN_SIZE {
+                continue;
+            }
+            for i in 0..clique.len() {
+                for j in i + 1..clique.len() {
+                    let idx = pairs
+                        .remove(&key(clique[i], clique[j]))
+                        .expect("no binary clause");
+                    occurs.remove(idx);
+                }
+            }
+            self.cards.add(clique, 1);
+            self.stats.detected_cards += 1;
+        }
+    }
+
+    /// Replace the clauses of `k + 1` literals that forbid any `k + 1` of a set of more than
+    /// `k + 1` literals to be true together by an at-most-k constraint.
+    /// A set grows greedily from the negations of a clause.
+    fn detect_at_most_k(&mut self, occurs: &mut Occurs, k: usize, ticks: &mut u64) {
+        let size = k + 1;
+        // clauses of `size` literals that aren't replaced yet by their sorted literals
+        let mut clauses: HashMap<Vec<Lit>, usize> = HashMap::new();
+        for (idx, clause) in occurs.clauses.iter().enumerate() {
+            if !occurs.removed[idx] && clause.len() == size {
+                let mut lits = clause.clone();
+                lits.sort();
+                clauses.insert(lits, idx);
+            }
+        }
+        for i in 0..occurs.clauses.len() {
+            if *ticks > CARD_DETECT_EFFORT {
+                break;
+            }
+            if occurs.removed[i] || occurs.clauses[i].len() != size {
+                continue;
+            }
+            // A duplicate of a clause that is already replaced is left as it is.
+            let mut lits = occurs.clauses[i].clone();
+            lits.sort();
+            if !clauses.contains_key(&lits) {
+                continue;
+            }
+            let mut set: Vec<Lit> = occurs.clauses[i].iter().map(|&lit| !lit).collect();
+            // Every clause of the constraint with `!set[0]` has the other literals negated.
+            let first = occurs.clauses[i][0];
+            let mut candidates: HashSet<Lit> = HashSet::new();
+            for j in occurs.occs(first).to_vec() {
+                if occurs.clauses[j].len() == size {
+                    candidates.extend(occurs.clauses[j].iter().map(|&lit| !lit));
+                }
+            }
+            *ticks += candidates.len() as u64;
+            let mut candidates: Vec<Lit> = candidates
+                .into_iter()
+                .filter(|lit| !set.contains(lit) && !set.contains(&!*lit))
+                .collect();
+            candidates.sort();
+            for x in candidates {
+                // `x` makes a clause with any `k` literals of the set.
+                let mut all = true;
+                for subset in combinations(set.len(), k) {
+                    *ticks += 1;
+                    let mut lits: Vec<Lit> = subset.iter().map(|&j| !set[j]).collect();
+                    lits.push(!x);
+                    lits.sort();
+                    if !clauses.contains_key(&lits) {
+                        all = false;
+                        break;
+                    }
+                }
+                if all {
+                    set.push(x);
+                }
+                if *ticks > CARD_DETECT_EFFORT {
+                    break;
+                }
+            }
+            if set.len() <= size {
+                continue;
+            }
+            for subset in combinations(set.len(), size) {
+                let mut lits: Vec<Lit> = subset.iter().map(|&j| !set[j]).collect();
+                lits.sort();
+                let idx = clauses.remove(&lits).expect("no clause of a subset");
+                occurs.remove(idx);
+            }
+            self.cards.add(set, k as u32);
+            self.stats.detected_cards += 1;
+        }
+    }
+
+    /// Count literals assigned since the last call in cardinality constraints and propagate
+    /// constraints where the weight of an unassigned literal exceeds the rest of their bounds.
+    /// Returns a conflict or whether any literal is assigned.
+    pub(super) fn propagate_cards(&mut self) -> Result<bool, Conflict> {
+        let end = self.vardata.trail.stack.len();
+        let mut propagated = false;
+        while self.cards.head < end {
+            let idx = self.cards.head;
+            let p = self.vardata.trail.stack[idx];
+            self.cards.head += 1;
+            if !self.cards.count(idx, p) {
+                continue;
+            }
+            for i in 0..self.cards.occs[p].len() {
+                let c = self.cards.occs[p][i].0 as usize;
+                let (count, card) = (self.cards.counts[c], &self.cards.cards[c]);
+                if count > card.k {
+                    return Err(self.card_conflict(c));
+                }
+                if count + card.weights[0] > card.k {
+                    propagated |= self.propagate_card(c);
+                }
+            }
+        }
+        Ok(propagated)
+    }
+
+    /// Assign false to the unassigned literals of a constraint whose weights exceed
+    /// the rest of its bound. An implied literal is explained by its constraint
+    /// only if `analyze` needs it.
+    fn propagate_card(&mut self, c: usize) -> bool {
+        let end = self.vardata.trail.stack.len();
+        let slack = self.cards.cards[c].k - self.cards.counts[c];
+        let mut propagated = false;
+        for i in 0..self.cards.cards[c].lits.len() {
+            let (lit, weight) = (self.cards.cards[c].lits[i], self.cards.cards[c].weights[i]);
+            if weight <= slack {
+                break;
+            }
+            if self.vardata.define(lit.var()) {
+                continue;
+            }
+            let reason = self.card_reason(c, end, weight);
+            let reason = self.explanation(reason);
+            let level = reason.first().map_or(0, |q| self.vardata.level(q.var()));
+            let explained = match reason.len() {
+                0 => Reason::Undef,
+                1 => Reason::Binary(reason[0]),
+                _ => Reason::Card(c as u32),
+            };
+            self.vardata.enqueue_at(!lit, level, explained);
+            self.stats.card_propagations += 1;
+            propagated = true;
+        }
+        propagated
+    }
+
+    /// Returns the negations of true literals at the lowest levels that are before `before`
+    /// in the trail, whose weights and `weight` exceed the bound of a constraint.
+    /// They imply the literals of `weight` false, or conflict if `weight` is 0.
+    fn card_reason(&self, c: usize, before: usize, weight: u64) -> Vec<Lit> {
+        let card = &self.cards.cards[c];
+        let vardata = &self.vardata;
+        let mut terms: Vec<(u64, Lit)> = card
+            .weights
+            .iter()
+            .copied()
+            .zip(card.lits.iter().copied())
+            .filter(|&(_, lit)| {
+                vardata.eval(lit) == LitBool::True && vardata.trail_index(lit.var()) < before
+            })
+            .collect();
+        terms.sort_by_key(|&(_, lit)| vardata.level(lit.var()));
+        let mut sum = weight;
+        let mut lits = Vec::new();
+        for (w, lit) in terms {
+            if sum > card.k {
+                break;
+            }
+            sum += w;
+            lits.push(!lit);
+        }
+        debug_assert!(sum > card.k);
+        lits
+    }
+
+    /// Returns the conflict of a constraint whose true literals exceed its bound,
+    /// which are the ones at the lowest levels.
+    fn card_conflict(&mut self, c: usize) -> Conflict {
+        self.stats.card_conflicts += 1;
+        let lits = self.card_reason(c, self.vardata.trail.stack.len(), 0);
+        self.explained_conflict(lits)
+    }
+
+    /// Returns the reason of an assigned variable `v`. The reason by a cardinality constraint
+    /// is explained as a clause, which replaces it.
+    pub(super) fn explain(&mut self, v: Var) -> Reason {
+        let c = match self.vardata.reason(v) {
+            Reason::Card(c) => c as usize,
+            reason => return reason,
+        };
+        let lit = Lit::new(v.val(), self.vardata.lbool(v) == LitBool::True);
+        let card = &self.cards.cards[c];
+        let i = card
+            .lits
+            .iter()
+            .position(|&q| q == !lit)
+            .expect("not in the constraint");
+        let reason = self.card_reason(c, self.vardata.trail_index(v), card.weights[i]);
+        let reason = self.explanation(reason);
+        let reason = self.explained_reason(lit, &reason);
+        self.vardata.set_reason(v, reason);
+        reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            config::Config,
+            elim::Occurs,
+            testing::{random_kcnf, solve_and_compare, Rng},
+            SatResult, Solver,
+        },
+        types::lit::Lit,
+    };
+
+    use super::combinations;
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(
+            combinations(4, 2),
+            vec![
+                vec![0, 1],
+                vec![0, 2],
+                vec![0, 3],
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 3]
+            ]
+        );
+        assert_eq!(combinations(3, 0), vec![Vec::<usize>::new()]);
+        assert!(combinations(2, 3).is_empty());
+    }
+
+    #[test]
+    fn test_add_at_most() {
+        let lits: Vec<Lit> = (0..5).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c) = (lits[0], lits[1], lits[2]);
+        let mut solver = Solver::new();
+        solver.add_at_most(&lits, 2);
+        assert_eq!(solver.solve_with_assumptions(&[a, b]), SatResult::Sat);
+        assert!(lits[2..]
+            .iter()
+            .all(|lit| solver.models[lit.var().0 as usize] == (!*lit).true_lbool()));
+        // `c` is false because of `a` and `b`, which explains the constraint.
+        assert_eq!(solver.solve_with_assumptions(&[a, b, c]), SatResult::Unsat);
+        let mut failed = solver.failed_assumptions.clone();
+        failed.sort();
+        assert_eq!(failed, vec![a, b, c]);
+        assert_eq!(solver.solve_with_assumptions(&[c, b]), SatResult::Sat);
+
+        // `a` and `!a` count as one true literal, so `b` and `c` are false.
+        let mut solver = Solver::new();
+        solver.add_at_most(&[a, !a, b, c], 1);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert_eq!(solver.models[1], (!b).true_lbool());
+        assert_eq!(solver.models[2], (!c).true_lbool());
+        solver.add_at_most(&[a, !a], 0);
+        assert_eq!(solver.solve(), SatResult::Unsat);
+    }
+
+    #[test]
+    fn test_add_pb() {
+        let lits: Vec<Lit> = (0..4).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d) = (lits[0], lits[1], lits[2], lits[3]);
+        // 3a + 2b + c + d >= 4 with a negative coefficient -2 !b = 2b - 2
+        let mut solver = Solver::new();
+        solver.add_pb(&[(3, a), (-2, !b), (1, c), (1, d)], 2);
+        assert_eq!(solver.solve_with_assumptions(&[!a]), SatResult::Sat);
+        assert!(lits[1..]
+            .iter()
+            .all(|lit| solver.models[lit.var().0 as usize] == lit.true_lbool()));
+        assert_eq!(solver.solve_with_assumptions(&[!a, !c]), SatResult::Unsat);
+        assert_eq!(solver.solve_with_assumptions(&[a, !c, !d]), SatResult::Sat);
+        assert_eq!(
+            solver.solve_with_assumptions(&[!b, !c, !d]),
+            SatResult::Unsat
+        );
+
+        // 5a + b + c >= 5 implies `a` because b + c < 5.
+        let mut solver = Solver::new();
+        solver.add_pb(&[(5, a), (1, b), (1, c)], 5);
+        assert_eq!(solver.solve_with_assumptions(&[!a]), SatResult::Unsat);
+        // 2a + 2!a >= 3 is 2 >= 3.
+        solver.add_pb(&[(2, a), (2, !a)], 3);
+        assert_eq!(solver.solve(), SatResult::Unsat);
+    }
+
+    #[test]
+    fn test_detect_cards() {
+        let lits: Vec<Lit> = (0..9).map(|i| Lit::new(i, true)).collect();
+        let mut solver = Solver::new();
+        let mut occurs = Occurs::new(9);
+        for _ in 0..9 {
+            solver.new_var();
+        }
+        // at most one of a, b, c and d
+        for i in 0..4 {
+            for j in i + 1..4 {
+                occurs.add(vec![!lits[i], !lits[j]]);
+            }
+        }
+        // at most two of e, f, g and h
+        for i in 4..8 {
+            let clause: Vec<Lit> = (4..8).filter(|&j| j != i).map(|j| !lits[j]).collect();
+            occurs.add(clause);
+        }
+        let other = occurs.add(vec![lits[0], lits[4], lits[8]]);
+        solver.detect_cards(&mut occurs);
+        assert_eq!(solver.stats.detected_cards, 2);
+        assert!((0..other).all(|idx| occurs.removed[idx]));
+        assert!(!occurs.removed[other]);
+        let mut cards: Vec<(Vec<Lit>, u64)> = solver
+            .cards
+            .cards
+            .iter()
+            .map(|card| {
+                let mut lits = card.lits.clone();
+                lits.sort();
+                (lits, card.k)
+            })
+            .collect();
+        cards.sort();
+        assert_eq!(
+            cards,
+            vec![(lits[0..4].to_vec(), 1), (lits[4..8].to_vec(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_solve_with_cards() {
+        let mut rng = Rng::new();
+        let mut conflicts = 0;
+        for _ in 0..20 {
+            let clauses = random_kcnf(&mut rng, 40, 100, 3);
+            let cards: Vec<(Vec<Lit>, u32)> = random_kcnf(&mut rng, 40, 12, 6)
+                .into_iter()
+                .zip((0..).map(|i| 1 + i % 3))
+                .collect();
+            let mut solver = Solver::new();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            cards
+                .iter()
+                .for_each(|(lits, k)| solver.add_at_most(lits, *k));
+
+            // Every `k + 1` literals of a constraint aren't true together.
+            let mut expected = Solver::with_config(Config {
+                card: false,
+                ..Config::default()
+            });
+            for (lits, k) in cards.iter() {
+                let mut lits = lits.clone();
+                lits.sort();
+                lits.dedup();
+                for subset in super::combinations(lits.len(), *k as usize + 1) {
+                    let clause: Vec<Lit> = subset.iter().map(|&i| !lits[i]).collect();
+                    expected.add_clause(&clause);
+                }
+            }
+            let result = solve_and_compare(&mut solver, expected, &clauses);
+            conflicts += solver.stats.card_conflicts;
+            if result == SatResult::Sat {
+                let value = |lit: &Lit| solver.models[lit.var().0 as usize] == lit.true_lbool();
+                for (lits, k) in cards.iter() {
+                    let mut lits = lits.clone();
+                    lits.sort();
+                    lits.dedup();
+                    assert!(lits.iter().filter(|lit| value(lit)).count() <= *k as usize);
+                }
+            }
+        }
+        assert!(conflicts > 0);
+    }
+}
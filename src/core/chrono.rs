@@ -0,0 +1,57 @@
+use crate::{clause::alloc::CRef, types::lit::Lit};
+
+use super::{Conflict, Solver};
+
+impl Solver {
+    /// Returns the highest level of the literals in a conflict and
+    /// whether only one literal is at the level.
+    /// Literals at the highest levels are moved to the front of the conflict
+    /// so that they are watched after backtracking.
+    pub(super) fn conflict_level(&mut self, confl: &mut Conflict) -> (u32, bool) {
+        let decision_level = self.vardata.trail.decision_level();
+        match confl {
+            Conflict::Binary(p, q) => {
+                if self.vardata.level(p.var()) < self.vardata.level(q.var()) {
+                    std::mem::swap(p, q);
+                }
+                let (p_level, q_level) = (self.vardata.level(p.var()), self.vardata.level(q.var()));
+                (p_level, p_level > q_level)
+            }
+            Conflict::Clause(cref) => {
+                let cref = *cref;
+                let clause = self.db.get_mut(cref);
+                let (w0, w1) = (clause[0], clause[1]);
+                if self.vardata.level(w0.var()) == decision_level
+                    && self.vardata.level(w1.var()) == decision_level
+                {
+                    return (decision_level, false);
+                }
+                let (c0, c1) = self.watch_highest(cref, w0, w1);
+                let (level0, level1) = (self.vardata.level(c0.var()), self.vardata.level(c1.var()));
+                (level0, level0 > level1)
+            }
+        }
+    }
+
+    /// Move the literals at the two highest levels of a clause to the front
+    /// and watch them instead of `w0` and `w1`. Returns the new watched literals.
+    pub(super) fn watch_highest(&mut self, cref: CRef, w0: Lit, w1: Lit) -> (Lit, Lit) {
+        let mut clause = self.db.get_mut(cref);
+        for i in 0..2 {
+            let mut max_idx = i;
+            for k in i + 1..clause.len() {
+                if self.vardata.level(clause[k].var()) > self.vardata.level(clause[max_idx].var()) {
+                    max_idx = k;
+                }
+            }
+            clause.swap(i, max_idx);
+        }
+        let (c0, c1) = (clause[0], clause[1]);
+        if !((c0 == w0 && c1 == w1) || (c0 == w1 && c1 == w0)) {
+            self.watches.unwatch(w0, cref);
+            self.watches.unwatch(w1, cref);
+            self.watches.watch(&[c0, c1], cref);
+        }
+        (c0, c1)
+    }
+}
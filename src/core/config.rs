@@ -0,0 +1,79 @@
+use super::{branching::BranchingStrategy, phase::PhaseConfig, restart::RestartStrategy};
+
+/// `Config` selects and tunes strategies of `Solver` at construction.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub restart: RestartStrategy,
+    pub branching: BranchingStrategy,
+    pub phase: PhaseConfig,
+    /// Backtrack only one level when a backjump would skip more levels than this.
+    /// `None` disables chronological backtracking. (default 100)
+    pub chrono: Option<u32>,
+    /// Restart only from the first decision that is worse than the next decision. (default true)
+    pub reuse_trail: bool,
+    /// Replace literals at the same level in a learnt clause by the UIP of the level. (default false)
+    pub shrink: bool,
+    /// Remove the implied literal from a reason clause that is subsumed by a resolvent
+    /// during conflict analysis. (default true)
+    pub otfs: bool,
+    /// The number of the most recent learnt clauses that are checked for subsumption
+    /// by a new learnt clause. 0 disables eager subsumption. (default 20)
+    pub eager_subsume: usize,
+    /// Shorten clauses by propagating the negation of their literals between restarts. (default true)
+    pub vivify: bool,
+    /// Probe roots of the binary implication graph between restarts
+    /// to learn units and hyper-binary resolvents. (default true)
+    pub probe: bool,
+    /// Remove subsumed and duplicate clauses and strengthen clauses by self-subsuming resolution
+    /// before search and learnt clauses between restarts. (default true)
+    pub subsume: bool,
+    /// Simplify clauses by time stamps of the binary implication graph between restarts. (default true)
+    pub unhide: bool,
+    /// Substitute equivalent literals found as strongly connected components
+    /// of the binary implication graph before search. (default true)
+    pub scc: bool,
+    /// Remove blocked clauses before search. (default true)
+    pub bce: bool,
+    /// Remove covered clauses as well as blocked clauses before search. (default false)
+    pub cce: bool,
+    /// Eliminate variables by bounded variable elimination before search. (default true)
+    pub elim: bool,
+    /// Recover XOR constraints encoded in clauses before search,
+    /// which are propagated by Gauss-Jordan elimination. (default true)
+    pub xor: bool,
+    /// Replace at-most-k constraints encoded in clauses before search
+    /// by native cardinality constraints. (default true)
+    pub card: bool,
+    /// Break symmetries found by the automorphism search of the graph of clauses and literals
+    /// by lex-leader constraints before the first search.
+    /// The constraints are removed once a later constraint or assumption
+    /// mentions a variable moved by the symmetries or one of their own variables,
+    /// which are numbered after the variables of the first search. (default false)
+    pub symmetry: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            restart: RestartStrategy::default(),
+            branching: BranchingStrategy::default(),
+            phase: PhaseConfig::default(),
+            chrono: Some(100),
+            reuse_trail: true,
+            shrink: false,
+            otfs: true,
+            eager_subsume: 20,
+            vivify: true,
+            probe: true,
+            subsume: true,
+            unhide: true,
+            scc: true,
+            bce: true,
+            cce: false,
+            elim: true,
+            xor: true,
+            card: true,
+            symmetry: false,
+        }
+    }
+}
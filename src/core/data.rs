@@ -1,12 +1,40 @@
 use analyzer::Analyzer;
 
 use crate::{
-    clause::{alloc::CRef, db::ClauseDB},
-    collections::{heap::Heap, idxvec::VarVec},
+    clause::{
+        alloc::{CRef, ClauseAllocator},
+        db::ClauseDB,
+    },
+    collections::idxvec::VarVec,
     types::{bool::LitBool, lit::Lit, var::Var},
 };
 
-use super::{analyzer, assign::AssignTrail};
+use super::{
+    analyzer,
+    assign::AssignTrail,
+    branching::Branching,
+    phase::{PhaseConfig, Phases},
+};
+
+/// `Reason` is why a variable is assigned.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Reason {
+    /// A decision or a unit clause
+    Undef,
+    /// A clause in `ClauseDB` whose first literal is the assigned one.
+    Clause(CRef),
+    /// A binary clause that holds the other (false) literal.
+    Binary(Lit),
+    /// A cardinality constraint, which is explained as a clause when `analyze` resolves it.
+    Card(u32),
+}
+
+impl Reason {
+    /// Returns true if the antecedents of an assignment are known without explaining it.
+    pub fn explained(self) -> bool {
+        matches!(self, Reason::Clause(_) | Reason::Binary(_))
+    }
+}
 
 /// VarData has basic information that is used for searching
 pub struct VarData {
@@ -14,27 +42,30 @@ pub struct VarData {
     assigns: VarVec<LitBool>,
     /// decision level
     level: VarVec<u32>,
-    /// CRef points a clause forces to assign a var.
-    reason: VarVec<CRef>,
+    /// a clause forces to assign a var.
+    reason: VarVec<Reason>,
+    /// the index of an assigned variable in `trail.stack`
+    trail_index: VarVec<usize>,
     /// a bunch of data is used to analyze conflicts.
     pub analyzer: Analyzer,
     pub trail: AssignTrail,
-    /// polarity
-    pub polarity: VarVec<LitBool>,
+    /// saved, target and best phases
+    pub phases: Phases,
     /// the decision order
-    pub order_heap: Heap,
+    pub branching: Box<dyn Branching>,
 }
 
 impl VarData {
-    pub fn new() -> VarData {
+    pub fn new(branching: Box<dyn Branching>, phase: PhaseConfig) -> VarData {
         VarData {
             assigns: VarVec::new(),
             level: VarVec::new(),
             reason: VarVec::new(),
+            trail_index: VarVec::new(),
             analyzer: Analyzer::new(),
             trail: AssignTrail::new(),
-            polarity: VarVec::new(),
-            order_heap: Heap::new(),
+            phases: Phases::new(phase),
+            branching,
         }
     }
     pub fn num_var(&self) -> usize {
@@ -44,106 +75,136 @@ impl VarData {
         let v = Var(self.num_var() as u32);
         self.assigns.push(LitBool::default());
         self.level.push(0);
-        self.reason.push(CRef::UNDEF);
-        self.polarity.push(LitBool::True);
-        self.analyzer.seen.push(false);
-
-        self.order_heap.push(v);
-    }
-
-    fn redundant(&mut self, root: CRef, db: &mut ClauseDB) -> bool {
-        // Check whether a literal can reach a decision variable or unit clause literal.
-        // Self-subsume
-
-        self.analyzer.ccmin_stack.clear();
-        self.analyzer.ccmin_stack.push(root);
-
-        let top = self.analyzer.ccmin_clear.len();
-        let mut redundant = true;
-        'redundant: while let Some(pos) = self.analyzer.ccmin_stack.pop() {
-            let clause = db.get_mut(pos);
-
-            for c in clause.iter().skip(1) {
-                if !self.analyzer.seen[c.var()] && self.level(c.var()) > 0 {
-                    // If a 'c' is decided by a level that is different from conflict literals.
-                    // abstract_level(c) & abstract_levels == 0
-                    let cref = self.reason(c.var());
-                    if cref != CRef::UNDEF {
-                        self.analyzer.seen[c.var()] = true;
-                        self.analyzer.ccmin_stack.push(cref);
-                        self.analyzer.ccmin_clear.push(*c);
-                    } else {
-                        redundant = false;
-                        break 'redundant;
-                    }
-                }
-            }
+        self.reason.push(Reason::Undef);
+        self.phases.new_var();
+        self.trail_index.push(0);
+        self.analyzer.new_var();
+
+        self.branching.new_var(v);
+    }
+
+    /// Mark a literal of a conflict or reason clause in the 1-UIP analysis.
+    /// A literal below `decision_level` goes to the learnt clause.
+    /// Returns true if a literal is newly marked at `decision_level`.
+    pub fn analyze_lit(&mut self, q: Lit, decision_level: u32) -> bool {
+        debug_assert!(self.eval(q) == LitBool::False);
+        if self.analyzer.seen[q.var()] {
+            return false;
+        }
+        self.analyzer.seen[q.var()] = true;
+        self.branching.bump(q.var());
+        if self.level(q.var()) < decision_level {
+            self.analyzer.learnt_clause.push(q);
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Returns the number of distinct decision levels in `lits`.
+    pub fn compute_lbd<'a>(&mut self, lits: impl Iterator<Item = &'a Lit>) -> u32 {
+        self.analyzer.lbd_counter = self.analyzer.lbd_counter.wrapping_add(1);
+        if self.analyzer.lbd_counter == 0 {
+            self.analyzer.lbd_stamp.iter_mut().for_each(|x| *x = 0);
+            self.analyzer.lbd_counter = 1;
         }
-        if !redundant {
-            // A 'c' is a decision variable or unit clause literal.
-            // which means a "lit" isn't redundant
-            for lit in self.analyzer.ccmin_clear.iter().skip(top) {
-                self.analyzer.seen[lit.var()] = false;
+        let stamp = self.analyzer.lbd_counter;
+        let mut lbd = 0;
+        for lit in lits {
+            let level = self.level[lit.var()] as usize;
+            if level >= self.analyzer.lbd_stamp.len() {
+                self.analyzer.lbd_stamp.resize(level + 1, 0);
+            }
+            if self.analyzer.lbd_stamp[level] != stamp {
+                self.analyzer.lbd_stamp[level] = stamp;
+                lbd += 1;
             }
-            self.analyzer.ccmin_clear.truncate(top);
         }
-        redundant
-    }
-
-    pub fn minimize_conflict_clause(&mut self, db: &mut ClauseDB) {
-        debug_assert!(self.analyzer.ccmin_clear.is_empty());
-        debug_assert!(self.analyzer.ccmin_stack.is_empty());
-
-        let n = self.analyzer.learnt_clause.len();
-        let mut new_size = 1;
-        for i in 1..n {
-            let lit = self.analyzer.learnt_clause[i];
-            let redundant = {
-                let cref = self.reason(lit.var());
-                if cref != CRef::UNDEF {
-                    self.redundant(cref, db)
-                } else {
-                    false
-                }
+        lbd
+    }
+
+    /// Rewrite reasons of assigned variables by `reloc`.
+    /// A reason clause that is deleted must be at level 0 and is dropped.
+    pub fn relocate_reasons(&mut self, db: &mut ClauseDB, to: &mut ClauseAllocator) {
+        for &lit in self.trail.stack.iter() {
+            let v = lit.var();
+            let cref = match self.reason[v] {
+                Reason::Clause(cref) => cref,
+                _ => continue,
             };
-            if !redundant {
-                self.analyzer.learnt_clause[new_size] = lit;
-                new_size += 1;
+            if db.get_mut(cref).deleted() {
+                debug_assert!(self.level[v] == 0);
+                self.reason[v] = Reason::Undef;
+            } else {
+                self.reason[v] = Reason::Clause(db.reloc(cref, to));
             }
         }
-        self.analyzer.learnt_clause.truncate(new_size);
+    }
+
+    /// Update target and best phases with the trail before the current decision level,
+    /// which is conflict-free.
+    pub fn update_phases(&mut self) {
+        if let Some(&sep) = self.trail.stack_lim.last() {
+            self.phases.update(&self.trail.stack[..sep]);
+        }
+    }
 
-        // clear
-        for lit in self.analyzer.ccmin_clear.iter() {
-            self.analyzer.seen[lit.var()] = false;
+    /// Returns the level to which a restart backtracks.
+    /// Decisions above `from_level` are kept as long as they are preferred to the next decision,
+    /// because they would be decided again after a restart.
+    pub fn reuse_trail(&mut self, from_level: u32) -> u32 {
+        let assigns = &self.assigns;
+        let next = match self.branching.peek(&|v| assigns[v] != LitBool::UnDef) {
+            Some(v) => v,
+            None => return self.trail.decision_level(),
+        };
+        for level in from_level..self.trail.decision_level() {
+            let decision = self.trail.stack[self.trail.stack_lim[level as usize]].var();
+            if self.branching.prefer(next, decision) {
+                return level;
+            }
         }
-        self.analyzer.ccmin_clear.clear();
-        self.analyzer.ccmin_stack.clear();
+        self.trail.decision_level()
     }
 
+    /// Unassign all variables whose level is greater than `backtrack_level`.
+    /// Variables at lower levels that were assigned out of order are kept in the trail.
     pub fn cancel_trail_until(&mut self, backtrack_level: u32) {
         if self.trail.decision_level() <= backtrack_level {
             return;
         }
-        let stack = &mut self.trail.stack;
         let sep = self.trail.stack_lim[backtrack_level as usize];
-        for p in stack.iter().skip(sep).rev() {
+        for idx in (sep..self.trail.stack.len()).rev() {
+            let p = self.trail.stack[idx];
             let v = p.var();
-            self.order_heap.push(v);
-            self.polarity[v] = p.true_lbool();
+            if self.level[v] <= backtrack_level {
+                continue;
+            }
+            self.branching.on_unassign(v);
+            self.phases.saved[v] = p.true_lbool();
             self.assigns[v] = LitBool::UnDef;
-            self.reason[v] = CRef::UNDEF;
+            self.reason[v] = Reason::Undef;
             self.level[v] = 0;
         }
+        let mut len = sep;
+        for idx in sep..self.trail.stack.len() {
+            let p = self.trail.stack[idx];
+            if self.define(p.var()) {
+                self.trail.stack[len] = p;
+                self.trail_index[p.var()] = len;
+                len += 1;
+            }
+        }
+        // The kept assignments are propagated again.
         self.trail.peek_head = sep;
-        self.trail.stack.truncate(sep);
+        self.trail.stack.truncate(len);
         self.trail.stack_lim.truncate(backtrack_level as usize);
     }
 
-    fn assign(&mut self, var: Var, lb: LitBool, level: u32, reason: CRef) {
+    fn assign(&mut self, var: Var, lb: LitBool, level: u32, reason: Reason) {
         debug_assert!(!self.define(var));
         debug_assert!(self.level(var) == 0);
-        debug_assert!(self.reason[var] == CRef::UNDEF);
+        debug_assert!(self.reason[var] == Reason::Undef);
         self.assigns[var] = lb;
         self.level[var] = level;
         self.reason[var] = reason;
@@ -165,18 +226,31 @@ impl VarData {
         self.level[var]
     }
 
-    pub fn reason(&self, var: Var) -> CRef {
+    pub fn reason(&self, var: Var) -> Reason {
         self.reason[var]
     }
 
-    pub fn enqueue(&mut self, lit: Lit, reason: CRef) {
+    /// Replace the reason of an assigned variable by an equivalent one.
+    pub fn set_reason(&mut self, var: Var, reason: Reason) {
+        debug_assert!(self.define(var));
+        self.reason[var] = reason;
+    }
+
+    pub fn trail_index(&self, var: Var) -> usize {
+        self.trail_index[var]
+    }
+
+    pub fn enqueue(&mut self, lit: Lit, reason: Reason) {
+        self.enqueue_at(lit, self.trail.decision_level(), reason);
+    }
+
+    /// Assign `lit` at `level` that may be lower than the current decision level.
+    pub fn enqueue_at(&mut self, lit: Lit, level: u32, reason: Reason) {
         debug_assert!(self.eval(lit) == LitBool::UnDef);
-        self.assign(
-            lit.var(),
-            lit.true_lbool(),
-            self.trail.decision_level(),
-            reason,
-        );
+        debug_assert!(level <= self.trail.decision_level());
+        self.assign(lit.var(), lit.true_lbool(), level, reason);
+        self.trail_index[lit.var()] = self.trail.stack.len();
+        self.branching.on_assign(lit.var());
         self.trail.push(lit);
     }
 }
@@ -0,0 +1,521 @@
+use crate::{
+    collections::idxvec::{LitVec, VarVec},
+    types::{bool::LitBool, lit::Lit, var::Var},
+};
+
+use super::{config::Config, data::Reason, subsume::SUBSUME_EFFORT, Solver};
+
+/// A resolvent that is longer than this stops the elimination of its pivot.
+const RESOLVENT_SIZE_LIMIT: usize = 20;
+/// Variables that have more pairs of positive and negative occurrences than this are not eliminated.
+const OCC_PRODUCT_LIMIT: usize = 400;
+/// The maximum number of literals visited by resolution in one elimination.
+const ELIM_EFFORT: u64 = 10_000_000;
+/// Candidates are tried again until a round eliminates nothing or this many rounds are done.
+pub(super) const ELIM_ROUNDS: usize = 3;
+
+/// A clause removed from the formula. Its first literal is flipped to satisfy it in `extend_model`.
+struct Removed {
+    lits: Vec<Lit>,
+    /// True if `lits` extends the previous clause by covered literals.
+    extension: bool,
+}
+
+/// `Eliminator` keeps the state of bounded variable elimination between `solve` calls.
+pub struct Eliminator {
+    enabled: bool,
+    /// Substitute equivalent literals.
+    pub(super) equivalences: bool,
+    /// Remove blocked clauses.
+    pub(super) blocked: bool,
+    /// Remove covered clauses as well as blocked clauses.
+    pub(super) covered: bool,
+    /// Frozen variables are never eliminated.
+    pub(super) frozen: VarVec<bool>,
+    eliminated: VarVec<bool>,
+    /// the number of removed clauses in `stack` whose first literal is of each variable
+    witnesses: VarVec<u32>,
+    /// Clauses removed by elimination in order.
+    stack: Vec<Removed>,
+    /// True if clauses are added or variables are melted since the last elimination.
+    pub(super) pending: bool,
+}
+
+impl Eliminator {
+    pub fn new(config: &Config) -> Eliminator {
+        Eliminator {
+            enabled: config.elim,
+            equivalences: config.scc,
+            blocked: config.bce,
+            covered: config.cce,
+            frozen: VarVec::new(),
+            eliminated: VarVec::new(),
+            witnesses: VarVec::new(),
+            stack: Vec::new(),
+            pending: true,
+        }
+    }
+
+    pub fn new_var(&mut self) {
+        self.frozen.push(false);
+        self.eliminated.push(false);
+        self.witnesses.push(0);
+    }
+
+    pub fn eliminated(&self, v: Var) -> bool {
+        self.eliminated[v]
+    }
+
+    /// Returns true if `v` is eliminated or some removed clauses are satisfied by flipping `v`.
+    /// Such a variable must be restored before it is used in a new clause or an assumption.
+    pub fn removed(&self, v: Var) -> bool {
+        self.eliminated[v] || self.witnesses[v] > 0
+    }
+
+    /// Mark `v` eliminated after all of its clauses are removed.
+    pub(super) fn mark_eliminated(&mut self, v: Var) {
+        self.eliminated[v] = true;
+    }
+
+    /// Push a removed clause whose first literal is flipped to satisfy it.
+    pub(super) fn push(&mut self, lits: Vec<Lit>, extension: bool) {
+        self.witnesses[lits[0].var()] += 1;
+        self.stack.push(Removed { lits, extension });
+    }
+
+    /// Drop the removed clauses that contain `v`, as if `v` was fixed to satisfy them
+    /// before they were removed.
+    pub(super) fn forget(&mut self, v: Var) {
+        let witnesses = &mut self.witnesses;
+        self.stack.retain(|removed| {
+            let keep = removed.lits.iter().all(|lit| lit.var() != v);
+            if !keep {
+                witnesses[removed.lits[0].var()] -= 1;
+            }
+            keep
+        });
+    }
+
+    /// Assign eliminated variables in `models` so that every clause removed by elimination is satisfied.
+    /// Clauses are visited in the reverse order of elimination.
+    pub fn extend_model(&self, models: &mut [LitBool]) {
+        for (i, model) in models.iter_mut().enumerate() {
+            if self.eliminated[Var::from_idx(i)] {
+                *model = LitBool::False;
+            }
+        }
+        for removed in self.stack.iter().rev() {
+            let clause = &removed.lits;
+            let satisfied = clause
+                .iter()
+                .any(|lit| models[lit.var().val() as usize] == lit.true_lbool());
+            if !satisfied {
+                let pivot = clause[0];
+                models[pivot.var().val() as usize] = pivot.true_lbool();
+            }
+        }
+    }
+}
+
+/// `Occurs` is a set of irredundant clauses with occurrence lists that elimination works on.
+pub(super) struct Occurs {
+    pub clauses: Vec<Vec<Lit>>,
+    pub removed: Vec<bool>,
+    occs: LitVec<Vec<usize>>,
+    /// temporary marks of literals
+    pub mark: LitVec<bool>,
+}
+
+impl Occurs {
+    pub fn new(num_var: usize) -> Occurs {
+        let mut occs = LitVec::new();
+        let mut mark = LitVec::new();
+        for _ in 0..2 * num_var {
+            occs.push(Vec::new());
+            mark.push(false);
+        }
+        Occurs {
+            clauses: Vec::new(),
+            removed: Vec::new(),
+            occs,
+            mark,
+        }
+    }
+
+    pub fn add(&mut self, lits: Vec<Lit>) -> usize {
+        let idx = self.clauses.len();
+        for &lit in lits.iter() {
+            self.occs[lit].push(idx);
+        }
+        self.clauses.push(lits);
+        self.removed.push(false);
+        idx
+    }
+
+    /// Remove a clause and returns its literals.
+    pub fn remove(&mut self, idx: usize) -> Vec<Lit> {
+        self.removed[idx] = true;
+        std::mem::take(&mut self.clauses[idx])
+    }
+
+    /// Remove a literal from a clause.
+    pub fn strengthen(&mut self, idx: usize, lit: Lit) {
+        self.clauses[idx].retain(|&l| l != lit);
+        self.occs[lit].retain(|&i| i != idx);
+    }
+
+    /// Returns the clauses that contain `lit`.
+    pub fn occs(&mut self, lit: Lit) -> &[usize] {
+        let removed = &self.removed;
+        self.occs[lit].retain(|&idx| !removed[idx]);
+        &self.occs[lit]
+    }
+
+    /// Returns the resolvent of two clauses on `v`, or `None` if it is a tautology.
+    fn resolve(&mut self, c: usize, d: usize, v: Var) -> Option<Vec<Lit>> {
+        let mut resolvent: Vec<Lit> = self.clauses[c]
+            .iter()
+            .copied()
+            .filter(|lit| lit.var() != v)
+            .collect();
+        for &lit in resolvent.iter() {
+            self.mark[lit] = true;
+        }
+        let mut tautology = false;
+        for &lit in self.clauses[d].iter() {
+            if lit.var() == v || self.mark[lit] {
+                continue;
+            }
+            if self.mark[!lit] {
+                tautology = true;
+                break;
+            }
+            resolvent.push(lit);
+        }
+        for &lit in self.clauses[c].iter() {
+            self.mark[lit] = false;
+        }
+        if tautology {
+            None
+        } else {
+            Some(resolvent)
+        }
+    }
+}
+
+impl Solver {
+    /// Freeze or melt a variable. Frozen variables are never eliminated,
+    /// so they can be used in clauses and assumptions of later `solve` calls as they are.
+    /// Freezing a variable brings back its clauses removed by elimination.
+    pub fn set_frozen(&mut self, v: Var, frozen: bool) {
+        while v.val() >= self.vardata.num_var() as u32 {
+            self.new_var();
+        }
+        if frozen && self.elim.removed(v) {
+            self.restore(v);
+        }
+        if self.elim.frozen[v] && !frozen {
+            self.elim.pending = true;
+        }
+        self.elim.frozen[v] = frozen;
+    }
+
+    /// Returns true if `v` is eliminated and doesn't appear in any clause.
+    pub fn is_eliminated(&self, v: Var) -> bool {
+        self.elim.eliminated(v)
+    }
+
+    /// Add the removed clauses that are satisfied by flipping `v` back to the formula.
+    /// A covered clause is restored as the original clause with all of its extensions.
+    pub(super) fn restore(&mut self, v: Var) {
+        let mut groups: Vec<Vec<Removed>> = Vec::new();
+        for removed in std::mem::take(&mut self.elim.stack) {
+            match groups.last_mut() {
+                Some(group) if removed.extension => group.push(removed),
+                _ => groups.push(vec![removed]),
+            }
+        }
+        let mut restored = Vec::new();
+        for group in groups {
+            if group.iter().all(|removed| removed.lits[0].var() != v) {
+                self.elim.stack.extend(group);
+                continue;
+            }
+            for removed in group.iter() {
+                self.elim.witnesses[removed.lits[0].var()] -= 1;
+            }
+            restored.push(group.into_iter().next().expect("empty group").lits);
+        }
+        if self.elim.eliminated[v] {
+            self.elim.eliminated[v] = false;
+            // `v` can be decided again.
+            self.vardata.branching.on_unassign(v);
+        }
+        for clause in restored.iter() {
+            self.add_clause(clause);
+        }
+    }
+
+    /// Recover XOR constraints, replace cardinality constraints, substitute equivalent literals,
+    /// subsume clauses, remove blocked clauses and eliminate variables if clauses are added
+    /// since the last elimination.
+    /// Clauses of variables in `assumptions` are kept as they are.
+    /// Returns false if the formula is found unsatisfiable.
+    pub(super) fn eliminate(&mut self) -> bool {
+        let enabled = self.elim.enabled
+            || self.elim.equivalences
+            || self.elim.blocked
+            || self.subsume.is_some()
+            || self.gauss.detect
+            || self.cards.detect;
+        if !self.elim.pending || !enabled {
+            return true;
+        }
+        self.elim.pending = false;
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        let mut occurs = self.take_irredundant();
+        self.detect_xors(&mut occurs);
+        self.detect_cards(&mut occurs);
+        // Frozen or assumed variables are never flipped by `extend_model`.
+        // Variables of XOR and cardinality constraints are kept as well, which aren't in `occurs`.
+        let mut frozen: Vec<bool> = (0..self.vardata.num_var())
+            .map(|i| self.elim.frozen[Var::from_idx(i)])
+            .collect();
+        for lit in self.assumptions.iter() {
+            frozen[lit.var().val() as usize] = true;
+        }
+        for v in self.gauss.vars().chain(self.cards.vars()) {
+            frozen[v.val() as usize] = true;
+        }
+        if self.elim.equivalences && !self.substitute_equivalences(&mut occurs, &frozen) {
+            return false;
+        }
+        if self.subsume.is_some() {
+            occurs.subsume(&mut self.stats, SUBSUME_EFFORT);
+        }
+        if self.elim.blocked {
+            self.eliminate_blocked(&mut occurs, &frozen);
+        }
+        if self.elim.enabled {
+            self.eliminate_vars(&mut occurs, &frozen);
+        }
+        self.rebuild(occurs)
+    }
+
+    /// Eliminate variables that are not `frozen` in the order of their numbers of occurrences.
+    fn eliminate_vars(&mut self, occurs: &mut Occurs, frozen: &[bool]) {
+        self.stats.eliminations += 1;
+        let mut ticks = 0;
+        for _ in 0..ELIM_ROUNDS {
+            let mut candidates: Vec<(usize, Var)> = Vec::new();
+            for v in (0..self.vardata.num_var()).map(Var::from_idx) {
+                if self.vardata.define(v) || self.elim.eliminated[v] || frozen[v.val() as usize] {
+                    continue;
+                }
+                let x = Lit::new(v.val(), true);
+                let cost = occurs.occs(x).len() + occurs.occs(!x).len();
+                candidates.push((cost, v));
+            }
+            candidates.sort_unstable();
+            let mut progress = false;
+            for &(_, v) in candidates.iter() {
+                if ticks > ELIM_EFFORT {
+                    break;
+                }
+                progress |= self.eliminate_var(occurs, v, &mut ticks);
+            }
+            if !progress {
+                break;
+            }
+        }
+    }
+
+    /// Move all irredundant clauses that are simplified at level 0 into `Occurs`.
+    fn take_irredundant(&mut self) -> Occurs {
+        let mut occurs = Occurs::new(self.vardata.num_var());
+        for i in 0..self.db.num_clauses() {
+            let cref = self.db.clauses()[i];
+            if self.db.get_mut(cref).deleted() {
+                continue;
+            }
+            let lits: Vec<Lit> = self.db.get_mut(cref).iter().copied().collect();
+            self.db.free(cref);
+            let (satisfied, lits) = self.simplify_clause(&lits);
+            if !satisfied {
+                occurs.add(lits);
+            }
+        }
+        self.db.retain_clauses();
+        for v in (0..self.vardata.num_var()).map(Var::from_idx) {
+            for p in [Lit::new(v.val(), true), Lit::new(v.val(), false)].iter() {
+                // `bw` watches `!p v bw.other`, which is visited twice.
+                for bw in self.watches.lookup_binary(*p).iter() {
+                    if !bw.learnt && !*p < bw.other {
+                        let (satisfied, lits) = self.simplify_clause(&[!*p, bw.other]);
+                        if !satisfied {
+                            occurs.add(lits);
+                        }
+                    }
+                }
+            }
+        }
+        self.watches.retain_binary(|_, bw| bw.learnt);
+        occurs
+    }
+
+    /// Replace the clauses of `v` by their resolvents if it doesn't increase the number of clauses.
+    /// If `v` is defined by a gate, only resolvents between gate and non-gate clauses are needed.
+    fn eliminate_var(&mut self, occurs: &mut Occurs, v: Var, ticks: &mut u64) -> bool {
+        let x = Lit::new(v.val(), true);
+        let pos = occurs.occs(x).to_vec();
+        let neg = occurs.occs(!x).to_vec();
+        if pos.len() * neg.len() > OCC_PRODUCT_LIMIT {
+            return false;
+        }
+        let gate = if pos.is_empty() || neg.is_empty() {
+            None
+        } else {
+            occurs.find_gate(v)
+        };
+        let bound = pos.len() + neg.len();
+        let mut resolvents = Vec::new();
+        for &c in pos.iter() {
+            for &d in neg.iter() {
+                if let Some((_, clauses)) = gate.as_ref() {
+                    if clauses.contains(&c) == clauses.contains(&d) {
+                        continue;
+                    }
+                }
+                *ticks += (occurs.clauses[c].len() + occurs.clauses[d].len()) as u64;
+                if let Some(resolvent) = occurs.resolve(c, d, v) {
+                    if resolvent.len() > RESOLVENT_SIZE_LIMIT || resolvents.len() >= bound {
+                        return false;
+                    }
+                    resolvents.push(resolvent);
+                }
+            }
+        }
+
+        for &idx in pos.iter().chain(neg.iter()) {
+            let mut clause = occurs.remove(idx);
+            let pivot = clause
+                .iter()
+                .position(|lit| lit.var() == v)
+                .expect("no pivot");
+            clause.swap(0, pivot);
+            self.elim.push(clause, false);
+        }
+        for resolvent in resolvents {
+            occurs.add(resolvent);
+        }
+        self.elim.mark_eliminated(v);
+        // Eliminated variables are never decided.
+        self.vardata.branching.on_assign(v);
+        self.stats.eliminated_vars += 1;
+        if gate.is_some() {
+            self.stats.gates += 1;
+        }
+        true
+    }
+
+    /// Put the remaining clauses of `occurs` back and remove learnt clauses with eliminated variables.
+    /// Returns false if the formula is found unsatisfiable.
+    fn rebuild(&mut self, occurs: Occurs) -> bool {
+        let mut units = Vec::new();
+        for (lits, removed) in occurs.clauses.into_iter().zip(occurs.removed) {
+            if removed {
+                continue;
+            }
+            match lits.len() {
+                0 => return false,
+                1 => units.push(lits[0]),
+                2 => self.watches.watch_binary(lits[0], lits[1], false),
+                _ => {
+                    let cref = self.db.alloc(&lits, false);
+                    self.watches.watch(&lits, cref);
+                }
+            }
+        }
+
+        for i in 0..self.db.num_learnts() {
+            let cref = self.db.learnts()[i];
+            let eliminated = &self.elim.eliminated;
+            if self
+                .db
+                .get_mut(cref)
+                .iter()
+                .any(|lit| eliminated[lit.var()])
+            {
+                self.db.free(cref);
+            }
+        }
+        self.db.retain_learnts();
+        let eliminated = &self.elim.eliminated;
+        self.watches
+            .retain_binary(|p, bw| !eliminated[p.var()] && !eliminated[bw.other.var()]);
+        self.watches.remove_deleted(&mut self.db);
+        if self.db.needs_gc() {
+            self.garbage_collect();
+        }
+
+        for lit in units {
+            match self.vardata.eval(lit) {
+                LitBool::True => {}
+                LitBool::False => return false,
+                _ => self.vardata.enqueue(lit, Reason::Undef),
+            }
+        }
+        self.propagate().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{SatResult, Solver},
+        types::{lit::Lit, var::Var},
+    };
+
+    #[test]
+    fn test_eliminate() {
+        let mut solver = Solver::new();
+        let lits: Vec<Lit> = (0..5).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d, e) = (lits[0], lits[1], lits[2], lits[3], lits[4]);
+        let clauses = [
+            vec![a, b],
+            vec![!a, c],
+            vec![!a, d, e],
+            vec![!b, !c],
+            vec![!d, !e],
+            vec![b, d],
+        ];
+        clauses.iter().for_each(|clause| solver.add_clause(clause));
+        solver.set_frozen(Var(3), true);
+        assert!(solver.eliminate());
+        assert!(solver.stats.eliminated_vars > 0);
+        assert!(!solver.is_eliminated(Var(3)));
+
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert!(clauses.iter().all(|clause| clause
+            .iter()
+            .any(|lit| solver.models[lit.var().0 as usize] == lit.true_lbool())));
+
+        // Clauses of an eliminated variable are brought back.
+        let v = (0..5).map(Var).find(|&v| solver.is_eliminated(v)).unwrap();
+        solver.add_clause(&[Lit::new(v.0, false)]);
+        assert!(!solver.is_eliminated(v));
+        let result = solver.solve();
+        if result == SatResult::Sat {
+            assert!(clauses.iter().all(|clause| clause
+                .iter()
+                .any(|lit| solver.models[lit.var().0 as usize] == lit.true_lbool())));
+            assert_eq!(
+                solver.models[v.0 as usize],
+                Lit::new(v.0, false).true_lbool()
+            );
+        }
+    }
+}
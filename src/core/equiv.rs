@@ -0,0 +1,270 @@
+use crate::{clause::alloc::CRef, collections::idxvec::LitVec, types::lit::Lit};
+
+use super::{elim::Occurs, Solver};
+
+/// Returns the strongly connected components of more than one literal in an implication graph
+/// by Tarjan's algorithm without recursion.
+fn strongly_connected(graph: &LitVec<Vec<Lit>>, num_var: usize) -> Vec<Vec<Lit>> {
+    let num_lit = 2 * num_var;
+    // 0 means that a literal isn't visited yet.
+    let mut index = vec![0; num_lit];
+    let mut low = vec![0; num_lit];
+    let mut on_stack = vec![false; num_lit];
+    let mut stack: Vec<Lit> = Vec::new();
+    let mut next_index = 1;
+    let mut components = Vec::new();
+    for root in (0..num_lit).map(Lit::from_idx) {
+        if index[root.val() as usize] != 0 {
+            continue;
+        }
+        // literals on the path from `root` with the next edges to visit
+        let mut path: Vec<(Lit, usize)> = vec![(root, 0)];
+        index[root.val() as usize] = next_index;
+        low[root.val() as usize] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root.val() as usize] = true;
+        while let Some(&mut (lit, ref mut edge)) = path.last_mut() {
+            let u = lit.val() as usize;
+            if let Some(&next) = graph[lit].get(*edge) {
+                *edge += 1;
+                let w = next.val() as usize;
+                if index[w] == 0 {
+                    index[w] = next_index;
+                    low[w] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[w] = true;
+                    path.push((next, 0));
+                } else if on_stack[w] {
+                    low[u] = low[u].min(index[w]);
+                }
+                continue;
+            }
+            path.pop();
+            if let Some(&(parent, _)) = path.last() {
+                let p = parent.val() as usize;
+                low[p] = low[p].min(low[u]);
+            }
+            if low[u] == index[u] {
+                let mut component = Vec::new();
+                loop {
+                    let top = stack.pop().expect("empty stack");
+                    on_stack[top.val() as usize] = false;
+                    component.push(top);
+                    if top == lit {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    components.push(component);
+                }
+            }
+        }
+    }
+    components
+}
+
+impl Solver {
+    /// Replace equivalent literals, which are strongly connected components of the binary
+    /// implication graph, by one representative in `occurs` and learnt clauses.
+    /// Replaced variables are eliminated with the clauses of their equivalences,
+    /// which assign them in `extend_model`. A `frozen` variable is never replaced.
+    /// Returns false if a literal is equivalent to its negation.
+    pub(super) fn substitute_equivalences(&mut self, occurs: &mut Occurs, frozen: &[bool]) -> bool {
+        self.stats.substitutions += 1;
+        let num_var = self.vardata.num_var();
+        let mut graph: LitVec<Vec<Lit>> = LitVec::new();
+        for _ in 0..2 * num_var {
+            graph.push(Vec::new());
+        }
+        for (clause, &removed) in occurs.clauses.iter().zip(occurs.removed.iter()) {
+            if !removed && clause.len() == 2 {
+                graph[!clause[0]].push(clause[1]);
+                graph[!clause[1]].push(clause[0]);
+            }
+        }
+        for p in (0..2 * num_var).map(Lit::from_idx) {
+            if self.vardata.define(p.var()) {
+                continue;
+            }
+            for bw in self.watches.lookup_binary(p).iter() {
+                if !self.vardata.define(bw.other.var()) {
+                    graph[p].push(bw.other);
+                }
+            }
+        }
+
+        let mut repr: Vec<Lit> = (0..2 * num_var).map(Lit::from_idx).collect();
+        let mut substituted = vec![false; num_var];
+        for component in strongly_connected(&graph, num_var) {
+            if component.iter().any(|&lit| component.contains(&!lit)) {
+                return false;
+            }
+            // The dual component of negations is already substituted.
+            if component.iter().any(|&lit| repr[lit.val() as usize] != lit) {
+                continue;
+            }
+            let rep = component
+                .iter()
+                .copied()
+                .find(|lit| frozen[lit.var().val() as usize])
+                .unwrap_or_else(|| *component.iter().min_by_key(|lit| lit.var()).unwrap());
+            for &lit in component.iter() {
+                let v = lit.var();
+                if lit == rep || frozen[v.val() as usize] {
+                    continue;
+                }
+                repr[lit.val() as usize] = rep;
+                repr[(!lit).val() as usize] = !rep;
+                substituted[v.val() as usize] = true;
+                self.elim.push(vec![lit, !rep], false);
+                self.elim.push(vec![!lit, rep], false);
+                self.elim.mark_eliminated(v);
+                // Substituted variables are never decided.
+                self.vardata.branching.on_assign(v);
+                self.stats.substituted_vars += 1;
+            }
+        }
+        if !substituted.contains(&true) {
+            return true;
+        }
+        let replace = |lits: &[Lit]| -> Option<Vec<Lit>> {
+            let mut lits: Vec<Lit> = lits.iter().map(|lit| repr[lit.val() as usize]).collect();
+            lits.sort();
+            lits.dedup();
+            if lits.windows(2).any(|w| w[0] == !w[1]) {
+                None
+            } else {
+                Some(lits)
+            }
+        };
+        let is_substituted = |lit: &Lit| substituted[lit.var().val() as usize];
+
+        let mut replaced = Occurs::new(num_var);
+        for (clause, &removed) in occurs.clauses.iter().zip(occurs.removed.iter()) {
+            if removed {
+                continue;
+            }
+            if let Some(lits) = replace(clause) {
+                replaced.add(lits);
+            }
+        }
+
+        // Learnt binary clauses
+        let mut binaries = Vec::new();
+        for p in (0..2 * num_var).map(Lit::from_idx) {
+            for bw in self.watches.lookup_binary(p).iter() {
+                if !p < bw.other && (is_substituted(&p) || is_substituted(&bw.other)) {
+                    binaries.push([!p, bw.other]);
+                }
+            }
+        }
+        self.watches
+            .retain_binary(|p, bw| !is_substituted(&p) && !is_substituted(&bw.other));
+        for lits in binaries.iter() {
+            match replace(lits) {
+                Some(lits) if lits.len() == 1 => {
+                    replaced.add(lits);
+                }
+                Some(lits) => self.watches.watch_binary(lits[0], lits[1], true),
+                None => {}
+            }
+        }
+
+        // Learnt long clauses
+        let learnts: Vec<CRef> = self.db.learnts().to_vec();
+        for cref in learnts {
+            let clause = self.db.get_mut(cref);
+            if clause.deleted() || !clause.iter().any(is_substituted) {
+                continue;
+            }
+            let lits: Vec<Lit> = clause.iter().copied().collect();
+            let lbd = clause.lbd();
+            self.watches.unwatch(lits[0], cref);
+            self.watches.unwatch(lits[1], cref);
+            self.db.free(cref);
+            match replace(&lits) {
+                Some(lits) if lits.len() == 1 => {
+                    replaced.add(lits);
+                }
+                Some(lits) if lits.len() == 2 => self.watches.watch_binary(lits[0], lits[1], true),
+                Some(lits) => {
+                    let cref = self.db.alloc(&lits, true);
+                    self.db.get_mut(cref).set_lbd(lbd);
+                    self.watches.watch(&lits, cref);
+                }
+                None => {}
+            }
+        }
+        self.db.retain_learnts();
+        *occurs = replaced;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collections::idxvec::LitVec,
+        core::{SatResult, Solver},
+        types::{lit::Lit, var::Var},
+    };
+
+    use super::strongly_connected;
+
+    #[test]
+    fn test_strongly_connected() {
+        let lits: Vec<Lit> = (0..3).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c) = (lits[0], lits[1], lits[2]);
+        let mut graph: LitVec<Vec<Lit>> = LitVec::new();
+        for _ in 0..6 {
+            graph.push(Vec::new());
+        }
+        // a -> b -> !c -> a and !a -> c -> !b -> !a
+        for &(from, to) in [(a, b), (b, !c), (!c, a), (!a, c), (c, !b), (!b, !a)].iter() {
+            graph[from].push(to);
+        }
+        let mut components: Vec<Vec<Lit>> = strongly_connected(&graph, 3)
+            .into_iter()
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        components.sort();
+        assert_eq!(components, vec![vec![a, b, !c], vec![!a, !b, c]]);
+    }
+
+    #[test]
+    fn test_substitute_equivalences() {
+        let mut solver = Solver::new();
+        let lits: Vec<Lit> = (0..5).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d, e) = (lits[0], lits[1], lits[2], lits[3], lits[4]);
+        // a = b = !c by a buffer and an inverter
+        let clauses = [
+            vec![!a, b],
+            vec![a, !b],
+            vec![!b, !c],
+            vec![b, c],
+            vec![a, c, d],
+            vec![b, d, e],
+            vec![!d, !e],
+        ];
+        clauses.iter().for_each(|clause| solver.add_clause(clause));
+        solver.set_frozen(Var(1), true);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert_eq!(solver.stats.substituted_vars, 2);
+        assert!(solver.is_eliminated(Var(0)) && solver.is_eliminated(Var(2)));
+        assert!(clauses.iter().all(|clause| clause
+            .iter()
+            .any(|lit| solver.models[lit.var().0 as usize] == lit.true_lbool())));
+
+        // `a` is restored with its equivalence.
+        assert_eq!(solver.solve_with_assumptions(&[a, !b]), SatResult::Unsat);
+        assert!(!solver.is_eliminated(Var(0)));
+        solver.add_clause(&[!c]);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert_eq!(solver.models[1], b.true_lbool());
+    }
+}
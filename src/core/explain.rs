@@ -0,0 +1,57 @@
+use crate::{clause::alloc::CRef, types::lit::Lit};
+
+use super::{data::Reason, Conflict, Solver};
+
+impl Solver {
+    /// Returns false literals of an explanation without literals at level 0
+    /// in the descending order of their levels.
+    pub(super) fn explanation(&self, mut lits: Vec<Lit>) -> Vec<Lit> {
+        lits.retain(|lit| self.vardata.level(lit.var()) > 0);
+        lits.sort_unstable_by_key(|lit| std::cmp::Reverse(self.vardata.level(lit.var())));
+        lits
+    }
+
+    /// Add a learnt clause that explains a propagation or a conflict of a constraint.
+    pub(super) fn learn_explanation(&mut self, lits: &[Lit]) -> CRef {
+        let cref = self.db.alloc(lits, true);
+        let vardata = &self.vardata;
+        let assigned: Vec<Lit> = lits
+            .iter()
+            .copied()
+            .filter(|lit| vardata.define(lit.var()))
+            .collect();
+        let lbd = self.vardata.compute_lbd(assigned.iter());
+        self.db.get_mut(cref).set_lbd(lbd);
+        self.watches.watch(lits, cref);
+        cref
+    }
+
+    /// Returns the reason of `lit` implied by a constraint whose other literals are `reason`.
+    /// It is learnt as a clause, so `analyze` resolves it as any other clause.
+    pub(super) fn explained_reason(&mut self, lit: Lit, reason: &[Lit]) -> Reason {
+        match reason.len() {
+            0 => Reason::Undef,
+            1 => {
+                self.watches.watch_binary(lit, reason[0], true);
+                Reason::Binary(reason[0])
+            }
+            _ => {
+                let mut lits = vec![lit];
+                lits.extend_from_slice(reason);
+                Reason::Clause(self.learn_explanation(&lits))
+            }
+        }
+    }
+
+    /// Returns the conflict of a constraint whose explanation `lits` are all false.
+    pub(super) fn explained_conflict(&mut self, lits: Vec<Lit>) -> Conflict {
+        let first = lits[0];
+        let lits = self.explanation(lits);
+        match lits.len() {
+            // `first` is false at level 0.
+            0 => Conflict::Binary(first, first),
+            1 => Conflict::Binary(lits[0], lits[0]),
+            _ => Conflict::Clause(self.learn_explanation(&lits)),
+        }
+    }
+}
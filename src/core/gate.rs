@@ -0,0 +1,240 @@
+use crate::types::{lit::Lit, var::Var};
+
+use super::elim::Occurs;
+
+/// The maximum number of variables in a detected XOR gate.
+const XOR_MAX_SIZE: usize = 4;
+
+/// `Gate` is a kind of definition of a variable by some of its clauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    /// `x = y`
+    Equivalence,
+    /// `x = y1 & y2 & ...`
+    And,
+    /// `x = if c then t else e`
+    Ite,
+    /// `x = y1 ^ y2 ^ ...`
+    Xor,
+}
+
+impl Occurs {
+    /// Returns a gate that defines `v` and the clauses of the gate.
+    /// Resolvents of two gate clauses or two non-gate clauses are redundant in elimination.
+    pub fn find_gate(&mut self, v: Var) -> Option<(Gate, Vec<usize>)> {
+        let x = Lit::new(v.val(), true);
+        self.find_and(x)
+            .or_else(|| self.find_and(!x))
+            .or_else(|| self.find_ite(x))
+            .or_else(|| self.find_xor(x))
+    }
+
+    /// Find `o = l1 & l2 & ...` that consists of `!o v li` and `o v !l1 v !l2 v ...`.
+    /// A gate of one input is an equivalence.
+    fn find_and(&mut self, o: Lit) -> Option<(Gate, Vec<usize>)> {
+        let mut inputs = Vec::new();
+        for &idx in self.occs(!o).to_vec().iter() {
+            let clause = &self.clauses[idx];
+            if clause.len() == 2 {
+                let lit = if clause[0] == !o {
+                    clause[1]
+                } else {
+                    clause[0]
+                };
+                if !self.mark[lit] {
+                    self.mark[lit] = true;
+                    inputs.push((lit, idx));
+                }
+            }
+        }
+        let mut gate = None;
+        if !inputs.is_empty() {
+            for &idx in self.occs(o).to_vec().iter() {
+                let clause = &self.clauses[idx];
+                if clause.iter().all(|&lit| lit == o || self.mark[!lit]) {
+                    let mut clauses = vec![idx];
+                    for &lit in clause.iter().filter(|&&lit| lit != o) {
+                        let &(_, binary) = inputs
+                            .iter()
+                            .find(|&&(input, _)| input == !lit)
+                            .expect("no input");
+                        clauses.push(binary);
+                    }
+                    let kind = if clause.len() == 2 {
+                        Gate::Equivalence
+                    } else {
+                        Gate::And
+                    };
+                    gate = Some((kind, clauses));
+                    break;
+                }
+            }
+        }
+        for &(lit, _) in inputs.iter() {
+            self.mark[lit] = false;
+        }
+        gate
+    }
+
+    /// Returns a ternary clause in `candidates` that consists of `o`, `p` and `q`.
+    fn find_ternary(&self, candidates: &[usize], o: Lit, p: Lit, q: Lit) -> Option<usize> {
+        candidates.iter().copied().find(|&idx| {
+            let clause = &self.clauses[idx];
+            clause.len() == 3 && [o, p, q].iter().all(|lit| clause.contains(lit))
+        })
+    }
+
+    /// Find `o = if c then t else e` that consists of
+    /// `!o v !c v t`, `!o v c v e`, `o v !c v !t` and `o v c v !e`.
+    fn find_ite(&mut self, o: Lit) -> Option<(Gate, Vec<usize>)> {
+        let pos = self.occs(o).to_vec();
+        let neg = self.occs(!o).to_vec();
+        for &i in neg.iter() {
+            let clause = &self.clauses[i];
+            if clause.len() != 3 {
+                continue;
+            }
+            let others: Vec<Lit> = clause.iter().copied().filter(|&lit| lit != !o).collect();
+            for &(not_c, t) in [(others[0], others[1]), (others[1], others[0])].iter() {
+                let c = !not_c;
+                for &j in neg.iter() {
+                    let clause = &self.clauses[j];
+                    if j == i || clause.len() != 3 || !clause.contains(&c) {
+                        continue;
+                    }
+                    let e = clause
+                        .iter()
+                        .copied()
+                        .find(|&lit| lit != !o && lit != c)
+                        .expect("no else literal");
+                    if e.var() == t.var() {
+                        continue;
+                    }
+                    let k = self.find_ternary(&pos, o, not_c, !t);
+                    let l = self.find_ternary(&pos, o, c, !e);
+                    if let (Some(k), Some(l)) = (k, l) {
+                        return Some((Gate::Ite, vec![i, j, k, l]));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Find `o ^ y1 ^ y2 ^ ... = b` that consists of all clauses over the variables
+    /// whose numbers of negative literals have the same parity.
+    fn find_xor(&mut self, o: Lit) -> Option<(Gate, Vec<usize>)> {
+        let mut candidates = self.occs(o).to_vec();
+        candidates.extend_from_slice(self.occs(!o));
+        for &i in candidates.iter() {
+            if !(3..=XOR_MAX_SIZE).contains(&self.clauses[i].len()) {
+                continue;
+            }
+            if let Some(clauses) = self.xor_clauses(i, &candidates) {
+                return Some((Gate::Xor, clauses));
+            }
+        }
+        None
+    }
+
+    /// Returns the clauses in `candidates` that encode an XOR constraint together with
+    /// the clause `i`, which are all clauses over its variables whose numbers of negative literals
+    /// have the same parity as `i`.
+    pub fn xor_clauses(&self, i: usize, candidates: &[usize]) -> Option<Vec<usize>> {
+        let clause = &self.clauses[i];
+        let size = clause.len();
+        let mut vars: Vec<Var> = clause.iter().map(|lit| lit.var()).collect();
+        vars.sort_unstable();
+        let parity = clause.iter().filter(|lit| lit.neg()).count() % 2;
+        // clauses of the constraint indexed by their negative literals
+        let mut found = vec![None; 1 << size];
+        for &j in candidates.iter() {
+            let other = &self.clauses[j];
+            if other.len() != size || other.iter().filter(|lit| lit.neg()).count() % 2 != parity {
+                continue;
+            }
+            let mut pattern = 0;
+            let mut matched = true;
+            for lit in other.iter() {
+                match vars.binary_search(&lit.var()) {
+                    Ok(k) if lit.neg() => pattern |= 1 << k,
+                    Ok(_) => {}
+                    Err(_) => {
+                        matched = false;
+                        break;
+                    }
+                }
+            }
+            if matched {
+                found[pattern] = Some(j);
+            }
+        }
+        let clauses: Vec<usize> = found.into_iter().flatten().collect();
+        if clauses.len() == 1 << (size - 1) {
+            Some(clauses)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::elim::Occurs,
+        types::{lit::Lit, var::Var},
+    };
+
+    use super::Gate;
+
+    fn occurs(clauses: &[Vec<Lit>]) -> Occurs {
+        let mut occurs = Occurs::new(8);
+        for clause in clauses.iter() {
+            occurs.add(clause.clone());
+        }
+        occurs
+    }
+
+    #[test]
+    fn test_find_gate() {
+        let lits: Vec<Lit> = (0..4).map(|i| Lit::new(i, true)).collect();
+        let (x, a, b, c) = (lits[0], lits[1], lits[2], lits[3]);
+
+        // x = a
+        let mut eq = occurs(&[vec![!x, a], vec![x, !a], vec![x, b, c]]);
+        assert_eq!(eq.find_gate(Var(0)), Some((Gate::Equivalence, vec![1, 0])));
+
+        // !x = a & b
+        let mut and = occurs(&[vec![x, a], vec![x, b], vec![!x, !a, !b], vec![x, c]]);
+        let (gate, mut clauses) = and.find_gate(Var(0)).unwrap();
+        clauses.sort_unstable();
+        assert_eq!((gate, clauses), (Gate::And, vec![0, 1, 2]));
+
+        // x = if a then b else c
+        let mut ite = occurs(&[
+            vec![!x, !a, b],
+            vec![!x, a, c],
+            vec![x, !a, !b],
+            vec![x, a, !c],
+            vec![x, b, c],
+        ]);
+        let (gate, mut clauses) = ite.find_gate(Var(0)).unwrap();
+        clauses.sort_unstable();
+        assert_eq!((gate, clauses), (Gate::Ite, vec![0, 1, 2, 3]));
+
+        // x ^ a ^ b = true
+        let mut xor = occurs(&[
+            vec![x, a, b],
+            vec![x, !a, !b],
+            vec![!x, a, !b],
+            vec![!x, !a, b],
+            vec![x, c],
+        ]);
+        let (gate, mut clauses) = xor.find_gate(Var(0)).unwrap();
+        clauses.sort_unstable();
+        assert_eq!((gate, clauses), (Gate::Xor, vec![0, 1, 2, 3]));
+
+        let mut none = occurs(&[vec![x, a, b], vec![!x, a, c]]);
+        assert_eq!(none.find_gate(Var(0)), None);
+    }
+}
@@ -0,0 +1,157 @@
+use super::Solver;
+
+impl Solver {
+    /// Move all live clauses into a fresh region and rewrite every `CRef`
+    /// held in watches, reasons and `ClauseDB`.
+    pub(super) fn garbage_collect(&mut self) {
+        self.stats.gcs += 1;
+        self.watches.remove_deleted(&mut self.db);
+        let mut to = self.db.gc_allocator();
+        let db = &mut self.db;
+        self.watches.relocate(|cref| db.reloc(cref, &mut to));
+        self.vardata.relocate_reasons(&mut self.db, &mut to);
+        self.db.finish_gc(to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            data::Reason,
+            reduce::{ReduceSchedule, TIER2_LBD},
+            testing::{random_kcnf, solve_and_compare, Rng},
+            Solver,
+        },
+        types::{lit::Lit, var::Var},
+    };
+
+    #[test]
+    fn test_relocate_after_gcs() {
+        let mut rng = Rng::new();
+        let lit = |v: u32| Lit::new(v, true);
+        let num_var = 40;
+        // clauses of distinct variables in `x10`, ..., `x38`
+        let mut random_clause = |len: usize| {
+            let mut clause: Vec<Lit> = Vec::new();
+            while clause.len() < len {
+                let v = (rng.next() % 29 + 10) as u32;
+                if clause.iter().all(|lit| lit.var().val() != v) {
+                    clause.push(Lit::new(v, rng.next().is_multiple_of(2)));
+                }
+            }
+            clause
+        };
+        let clauses: Vec<Vec<Lit>> = (0..60).map(|_| random_clause(3)).collect();
+        // `x0` implies `x1`, ..., `x9` by clauses of three literals, half of which are learnt.
+        let chain: Vec<Vec<Lit>> = (1..10)
+            .map(|i| vec![lit(i), !lit(i - 1), lit(39)])
+            .collect();
+
+        let mut solver = Solver::new();
+        solver.db.set_garbage_frac(0.0);
+        while solver.num_var() < num_var {
+            solver.new_var();
+        }
+        clauses.iter().for_each(|clause| solver.add_clause(clause));
+        solver.add_clause(&[!lit(39)]);
+        for (i, clause) in chain.iter().enumerate() {
+            let cref = solver.db.alloc(clause, i % 2 == 0);
+            if i % 2 == 0 {
+                // Locked learnt clauses are never reduced.
+                solver.db.get_mut(cref).set_lbd(TIER2_LBD + 1);
+            }
+            solver.watches.watch(clause, cref);
+        }
+        solver.vardata.trail.new_decision_level();
+        solver.vardata.enqueue(lit(0), Reason::Undef);
+        assert!(solver.propagate().is_none());
+
+        let mut locals = 0;
+        for round in 1..=5 {
+            // Learnt clauses implied by the formula.
+            for i in 0..100 {
+                let mut learnt = clauses[(i * 7 + round) % clauses.len()].clone();
+                let extra = random_clause(1)[0];
+                if learnt.iter().any(|lit| lit.var() == extra.var()) {
+                    continue;
+                }
+                learnt.push(extra);
+                let cref = solver.db.alloc(&learnt, true);
+                solver.db.get_mut(cref).set_lbd(TIER2_LBD + 1);
+                solver.watches.watch(&learnt, cref);
+                locals += 1;
+            }
+            solver.reduce_db();
+            locals -= locals / 2;
+            assert_eq!(solver.stats.gcs, 2 * round as u64 - 1);
+            solver.garbage_collect();
+            assert_eq!(solver.stats.gcs, 2 * round as u64);
+            assert_eq!(solver.db.num_learnts(), locals + 5);
+
+            // The chain is still explained by the relocated clauses.
+            for (i, clause) in chain.iter().enumerate() {
+                let v = Var::from_idx(i + 1);
+                let cref = match solver.vardata.reason(v) {
+                    Reason::Clause(cref) => cref,
+                    _ => panic!("x{} is not implied by a clause", i + 1),
+                };
+                let reason = solver.db.get_mut(cref);
+                assert!(!reason.deleted());
+                assert_eq!(reason[0], clause[0]);
+                let mut lits: Vec<Lit> = reason.iter().copied().collect();
+                lits.sort();
+                let mut expected = clause.clone();
+                expected.sort();
+                assert_eq!(lits, expected);
+            }
+            // Each live clause is watched by its first two literals, and each watch points
+            // to a live clause.
+            let crefs: Vec<_> = solver
+                .db
+                .clauses()
+                .iter()
+                .chain(solver.db.learnts().iter())
+                .copied()
+                .collect();
+            for &cref in crefs.iter() {
+                let clause = solver.db.get_mut(cref);
+                assert!(!clause.deleted());
+                let (c0, c1) = (clause[0], clause[1]);
+                for &c in [c0, c1].iter() {
+                    assert!(solver.watches.lookup_mut(!c).iter().any(|w| w.cref == cref));
+                }
+            }
+            for v in 0..num_var as u32 {
+                for &p in [lit(v), !lit(v)].iter() {
+                    let watches: Vec<_> = solver
+                        .watches
+                        .lookup_mut(p)
+                        .iter()
+                        .map(|w| w.cref)
+                        .collect();
+                    for cref in watches {
+                        assert!(crefs.contains(&cref));
+                        let clause = solver.db.get_mut(cref);
+                        assert!(clause[0] == !p || clause[1] == !p);
+                    }
+                }
+            }
+        }
+
+        let mut formula: Vec<Vec<Lit>> = clauses.iter().chain(chain.iter()).cloned().collect();
+        formula.push(vec![!lit(39)]);
+        solve_and_compare(&mut solver, Solver::new(), &formula);
+    }
+
+    #[test]
+    fn test_solve_with_many_gcs() {
+        let clauses = random_kcnf(&mut Rng::new(), 150, 640, 3);
+        let mut solver = Solver::new();
+        solver.reduce = ReduceSchedule::new(20, 2);
+        solver.db.set_garbage_frac(0.0);
+        clauses.iter().for_each(|clause| solver.add_clause(clause));
+        solve_and_compare(&mut solver, Solver::new(), &clauses);
+        assert!(solver.stats.gcs >= 10, "{}", solver.stats);
+    }
+}
@@ -1,20 +1,31 @@
+use super::restart::RestartPolicy;
+
 /// `LubyRestart`
 pub struct LubyRestart {
     /// The initial restart limit. (default 100)
     first: i32,
     /// The factor with which the restart limit is multiplied in each restart. (default 2.5)
     inc: f64,
+    restart_cnt: i32,
+    /// The number of conflicts since the last restart
+    conflicts: u64,
 }
 impl Default for LubyRestart {
     fn default() -> Self {
-        LubyRestart {
-            first: 100,
-            inc: 2.5,
-        }
+        LubyRestart::new(100, 2.5)
     }
 }
 
 impl LubyRestart {
+    pub fn new(first: i32, inc: f64) -> LubyRestart {
+        LubyRestart {
+            first,
+            inc,
+            restart_cnt: 0,
+            conflicts: 0,
+        }
+    }
+
     /// `seq` returns
     pub fn seq(&mut self, mut restart_cnt: i32) -> f64 {
         let mut size = 1;
@@ -31,3 +42,31 @@ impl LubyRestart {
         f64::powi(self.inc, seq) * self.first as f64
     }
 }
+
+impl RestartPolicy for LubyRestart {
+    fn on_conflict(&mut self, _lbd: u32, _trail_size: usize) {
+        self.conflicts += 1;
+    }
+    fn should_restart(&mut self) -> bool {
+        self.conflicts as f64 >= self.seq(self.restart_cnt)
+    }
+    fn on_restart(&mut self) {
+        self.restart_cnt += 1;
+        self.conflicts = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LubyRestart;
+
+    #[test]
+    fn test_luby_seq() {
+        let mut luby = LubyRestart::new(1, 2.0);
+        let seq: Vec<f64> = (0..15).map(|i| luby.seq(i)).collect();
+        assert_eq!(
+            seq,
+            vec![1., 1., 2., 1., 1., 2., 4., 1., 1., 2., 1., 1., 2., 4., 8.]
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::{collections::HashMap, convert::TryFrom};
+
+use crate::types::{bool::LitBool, lit::Lit};
+
+use super::{optimize::Optimum, SatResult, Solver};
+
+/// `MaxSatStrategy` selects how `solve_maxsat` searches for the minimum cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaxSatStrategy {
+    /// Stratified core-guided search of OLL, which raises the lower bound by each core
+    /// of failed assumptions and relaxes the core by a cardinality constraint.
+    #[default]
+    CoreGuided,
+    /// Model-improving linear search, which tightens the upper bound after each model.
+    Linear,
+}
+
+impl std::str::FromStr for MaxSatStrategy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "oll" => Ok(MaxSatStrategy::CoreGuided),
+            "linear" => Ok(MaxSatStrategy::Linear),
+            _ => Err(format!("unknown MaxSAT strategy: {}", s)),
+        }
+    }
+}
+
+impl Solver {
+    /// Returns the sum of the weights of the clauses of `softs` that are falsified in `models`.
+    pub fn soft_cost(&self, softs: &[(u64, Vec<Lit>)]) -> i64 {
+        softs
+            .iter()
+            .filter(|(_, clause)| {
+                !clause
+                    .iter()
+                    .any(|lit| self.models[lit.var().val() as usize] == lit.true_lbool())
+            })
+            .map(|&(w, _)| i64::try_from(w).expect("too large weight"))
+            .sum()
+    }
+
+    /// Minimize the sum of the weights of the falsified clauses of `softs`
+    /// while the clauses added to the solver are hard.
+    /// `improved` is called with the cost of each better model.
+    /// The solver keeps the relaxed soft clauses and the constraints of the search after the call.
+    pub fn solve_maxsat<F: FnMut(i64)>(
+        &mut self,
+        softs: &[(u64, Vec<Lit>)],
+        strategy: MaxSatStrategy,
+        mut improved: F,
+    ) -> Optimum {
+        let (constant, objective) = self.relax_softs(softs);
+        match strategy {
+            MaxSatStrategy::CoreGuided => self.solve_oll(softs, constant, objective, improved),
+            MaxSatStrategy::Linear => {
+                match self.minimize(&objective, |cost| improved(constant + cost)) {
+                    Optimum::Found(cost) => Optimum::Found(constant + cost),
+                    Optimum::Unsat => Optimum::Unsat,
+                    Optimum::Unknown(cost) => Optimum::Unknown(cost.map(|cost| constant + cost)),
+                }
+            }
+        }
+    }
+
+    /// Add soft clauses as hard clauses with new relaxation literals.
+    /// Returns the cost of empty clauses and the objective of literals that are true
+    /// if the clauses are falsified.
+    fn relax_softs(&mut self, softs: &[(u64, Vec<Lit>)]) -> (i64, Vec<(i64, Lit)>) {
+        // Relaxation variables follow the variables of soft clauses.
+        let num_var = softs
+            .iter()
+            .flat_map(|(_, clause)| clause.iter())
+            .map(|lit| lit.var().val() as usize + 1)
+            .max()
+            .unwrap_or(0);
+        while self.num_var() < num_var {
+            self.new_var();
+        }
+        let mut constant = 0;
+        let mut objective = Vec::with_capacity(softs.len());
+        for (w, clause) in softs.iter() {
+            let w = i64::try_from(*w).expect("too large weight");
+            match clause.as_slice() {
+                _ if w == 0 => {}
+                [] => constant += w,
+                &[lit] => objective.push((w, !lit)),
+                _ => {
+                    let b = Lit::new(self.new_var().val(), true);
+                    let mut relaxed = clause.clone();
+                    relaxed.push(b);
+                    self.add_clause(&relaxed);
+                    objective.push((w, b));
+                }
+            }
+        }
+        objective.sort_by_key(|&(_, lit)| lit);
+        let mut merged: Vec<(i64, Lit)> = Vec::with_capacity(objective.len());
+        for (w, lit) in objective {
+            match merged.last_mut() {
+                Some((last, lit2)) if *lit2 == lit => *last += w,
+                _ => merged.push((w, lit)),
+            }
+        }
+        (constant, merged)
+    }
+
+    /// Returns a new literal that is true if at least `k` of `lits` are true.
+    fn at_least_output(&mut self, lits: &[Lit], k: usize) -> Lit {
+        let output = Lit::new(self.new_var().val(), true);
+        self.set_frozen(output.var(), true);
+        // The sum of `lits` is at most `k - 1` unless `output` is true.
+        let mut terms: Vec<(i64, Lit)> = lits.iter().map(|&lit| (-1, lit)).collect();
+        terms.push(((lits.len() + 1 - k) as i64, output));
+        self.add_pb(&terms, 1 - k as i64);
+        output
+    }
+
+    /// OLL with stratification. Literals in `terms` are assumed to be false
+    /// in the order of their weights, and each core of them raises the lower bound by
+    /// its minimum weight. The weights of the literals in the core are decreased by it,
+    /// and the outputs of a cardinality constraint over the core are new literals to assume
+    /// false: the output that at least `k` of them are true costs the minimum weight for `k >= 2`.
+    /// An output is added lazily after the previous output appears in a core.
+    fn solve_oll<F: FnMut(i64)>(
+        &mut self,
+        softs: &[(u64, Vec<Lit>)],
+        mut lower: i64,
+        mut terms: Vec<(i64, Lit)>,
+        mut improved: F,
+    ) -> Optimum {
+        for &(_, lit) in terms.iter() {
+            self.set_frozen(lit.var(), true);
+        }
+        let mut index: HashMap<Lit, usize> = terms
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, lit))| (lit, i))
+            .collect();
+        // the literals and the weight of the cardinality constraint of each core
+        let mut sums: Vec<(Vec<Lit>, i64)> = Vec::new();
+        // the constraint and `k` of the last output of each constraint
+        let mut outputs: HashMap<Lit, (usize, usize)> = HashMap::new();
+        let mut best: Option<(i64, Vec<LitBool>)> = None;
+        let mut stratum = terms.iter().map(|&(w, _)| w).max().unwrap_or(0);
+        loop {
+            let assumptions: Vec<Lit> = terms
+                .iter()
+                .filter(|&&(w, _)| w > 0 && w >= stratum)
+                .map(|&(_, lit)| !lit)
+                .collect();
+            match self.solve_with_assumptions(&assumptions) {
+                SatResult::Sat => {
+                    let cost = self.soft_cost(softs);
+                    if best.as_ref().is_none_or(|&(last, _)| cost < last) {
+                        improved(cost);
+                        best = Some((cost, self.models.clone()));
+                    }
+                    let last = best.as_ref().map_or(cost, |&(last, _)| last);
+                    // Lower the stratum to the next weight unless the bounds meet.
+                    match terms
+                        .iter()
+                        .map(|&(w, _)| w)
+                        .filter(|&w| w > 0 && w < stratum)
+                        .max()
+                    {
+                        Some(w) if lower < last => stratum = w,
+                        _ => {
+                            debug_assert_eq!(lower, last);
+                            let (cost, models) = best.expect("a model is found");
+                            self.models = models;
+                            return Optimum::Found(cost);
+                        }
+                    }
+                }
+                SatResult::Unsat => {
+                    if self.failed_assumptions.is_empty() {
+                        debug_assert!(best.is_none());
+                        return Optimum::Unsat;
+                    }
+                    let core: Vec<Lit> = self.failed_assumptions.iter().map(|&lit| !lit).collect();
+                    let min = core
+                        .iter()
+                        .map(|lit| terms[index[lit]].0)
+                        .min()
+                        .expect("a core is not empty");
+                    lower += min;
+                    let mut new_terms = Vec::new();
+                    for lit in core.iter() {
+                        terms[index[lit]].0 -= min;
+                        if let Some((s, k)) = outputs.remove(lit) {
+                            if k < sums[s].0.len() {
+                                let lits = sums[s].0.clone();
+                                let output = self.at_least_output(&lits, k + 1);
+                                outputs.insert(output, (s, k + 1));
+                                new_terms.push((sums[s].1, output));
+                            }
+                        }
+                    }
+                    if core.len() > 1 {
+                        let output = self.at_least_output(&core, 2);
+                        outputs.insert(output, (sums.len(), 2));
+                        sums.push((core, min));
+                        new_terms.push((min, output));
+                    }
+                    for (w, lit) in new_terms {
+                        index.insert(lit, terms.len());
+                        terms.push((w, lit));
+                    }
+                }
+                SatResult::Unknown => {
+                    return match best {
+                        Some((cost, models)) => {
+                            self.models = models;
+                            Optimum::Unknown(Some(cost))
+                        }
+                        None => Optimum::Unknown(None),
+                    };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            optimize::Optimum,
+            testing::{bits_satisfy, brute_force, models_satisfy, random_kcnf, Rng},
+            Solver,
+        },
+        types::lit::Lit,
+    };
+
+    use super::MaxSatStrategy;
+
+    #[test]
+    fn test_solve_maxsat() {
+        let mut rng = Rng::new();
+        for _ in 0..30 {
+            let num_var = 10;
+            let hards = random_kcnf(&mut rng, num_var, 15, 3);
+            let softs: Vec<(u64, Vec<Lit>)> = (0..20)
+                .map(|i| {
+                    (
+                        i % 4 * 3 + 1,
+                        (0..i % 3).map(|_| rng.lit(num_var)).collect(),
+                    )
+                })
+                .collect();
+
+            // the minimum cost of all models by brute force
+            let expected = brute_force(num_var, &hards)
+                .map(|bits| {
+                    softs
+                        .iter()
+                        .filter(|(_, clause)| !bits_satisfy(bits, clause))
+                        .map(|&(w, _)| w as i64)
+                        .sum::<i64>()
+                })
+                .min();
+            for &strategy in [MaxSatStrategy::CoreGuided, MaxSatStrategy::Linear].iter() {
+                let mut solver = Solver::new();
+                hards.iter().for_each(|clause| solver.add_clause(clause));
+                let mut costs = Vec::new();
+                let optimum = solver.solve_maxsat(&softs, strategy, |cost| costs.push(cost));
+                match expected {
+                    Some(cost) => {
+                        assert_eq!(optimum, Optimum::Found(cost), "{:?}", strategy);
+                        assert_eq!(solver.soft_cost(&softs), cost);
+                        assert!(models_satisfy(&solver.models, &hards));
+                        assert_eq!(costs.last(), Some(&cost));
+                        assert!(costs.windows(2).all(|w| w[0] > w[1]));
+                    }
+                    None => assert_eq!(optimum, Optimum::Unsat),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    clause::db::ClauseDB,
+    types::{bool::LitBool, lit::Lit},
+};
+
+use super::{
+    data::{Reason, VarData},
+    Solver,
+};
+
+/// Learnt clauses that are larger than this are not strengthened by binary clauses.
+const BINARY_STRENGTHEN_SIZE: usize = 30;
+/// Learnt clauses whose LBD is larger than this are not strengthened by binary clauses.
+const BINARY_STRENGTHEN_LBD: u32 = 6;
+
+/// Returns the `i`-th literal of a `reason` that implies a variable.
+fn antecedent(db: &mut ClauseDB, reason: Reason, i: usize) -> Option<Lit> {
+    match reason {
+        Reason::Clause(cref) => {
+            let clause = db.get_mut(cref);
+            if i + 1 < clause.len() {
+                Some(clause[i + 1])
+            } else {
+                None
+            }
+        }
+        Reason::Binary(q) if i == 0 => Some(q),
+        _ => None,
+    }
+}
+
+/// A hash of a decision level.
+/// A literal isn't redundant if its abstract level isn't in the learnt clause.
+fn abstract_level(level: u32) -> u32 {
+    1 << (level & 31)
+}
+
+impl VarData {
+    /// Returns true if `p` is implied by literals in the learnt clause.
+    /// Literals that are found to be removable are cached in `seen`
+    /// and ones that are not removable are cached in `poison`.
+    fn redundant(&mut self, p: Lit, abstract_levels: u32, db: &mut ClauseDB) -> bool {
+        debug_assert!(self.reason(p.var()).explained());
+        self.analyzer.ccmin_stack.clear();
+        let (mut p, mut i) = (p, 0);
+        loop {
+            match antecedent(db, self.reason(p.var()), i) {
+                Some(q) => {
+                    i += 1;
+                    let v = q.var();
+                    if self.level(v) == 0 || self.analyzer.seen[v] {
+                        continue;
+                    }
+                    // A cardinality constraint isn't explained before `analyze` resolves it.
+                    if !self.reason(v).explained()
+                        || self.analyzer.poison[v]
+                        || abstract_level(self.level(v)) & abstract_levels == 0
+                    {
+                        // `p` and literals on the path are implied by `q` that isn't removable.
+                        self.analyzer.ccmin_stack.push((p, i));
+                        for k in 0..self.analyzer.ccmin_stack.len() {
+                            let (lit, _) = self.analyzer.ccmin_stack[k];
+                            if !self.analyzer.seen[lit.var()] && !self.analyzer.poison[lit.var()] {
+                                self.analyzer.poison[lit.var()] = true;
+                                self.analyzer.ccmin_clear.push(lit);
+                            }
+                        }
+                        return false;
+                    }
+                    self.analyzer.ccmin_stack.push((p, i));
+                    p = q;
+                    i = 0;
+                }
+                None => {
+                    // All antecedents of `p` are removable.
+                    if !self.analyzer.seen[p.var()] {
+                        self.analyzer.seen[p.var()] = true;
+                        self.analyzer.ccmin_clear.push(p);
+                    }
+                    match self.analyzer.ccmin_stack.pop() {
+                        Some((q, j)) => {
+                            p = q;
+                            i = j;
+                        }
+                        None => return true,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove literals that are implied by the other literals from the learnt clause.
+    /// If `shrink` is true, literals at the same level are replaced by the UIP of the level.
+    pub fn minimize_conflict_clause(&mut self, db: &mut ClauseDB, shrink: bool) {
+        debug_assert!(self.analyzer.ccmin_clear.is_empty());
+
+        let learnt_clause = std::mem::take(&mut self.analyzer.learnt_clause);
+        let abstract_levels = learnt_clause.iter().skip(1).fold(0, |levels, lit| {
+            levels | abstract_level(self.level(lit.var()))
+        });
+        self.analyzer.learnt_clause = learnt_clause;
+
+        let n = self.analyzer.learnt_clause.len();
+        let mut new_size = 1;
+        for i in 1..n {
+            let lit = self.analyzer.learnt_clause[i];
+            let redundant =
+                self.reason(lit.var()).explained() && self.redundant(lit, abstract_levels, db);
+            if !redundant {
+                self.analyzer.learnt_clause[new_size] = lit;
+                new_size += 1;
+            }
+        }
+        self.analyzer.learnt_clause.truncate(new_size);
+
+        if shrink {
+            self.shrink(abstract_levels, db);
+        }
+
+        // clear
+        for lit in self.analyzer.ccmin_clear.iter() {
+            self.analyzer.seen[lit.var()] = false;
+            self.analyzer.poison[lit.var()] = false;
+        }
+        self.analyzer.ccmin_clear.clear();
+        self.analyzer.ccmin_stack.clear();
+    }
+
+    /// Replace literals of the learnt clause at each level by the UIP of the level if possible.
+    fn shrink(&mut self, abstract_levels: u32, db: &mut ClauseDB) {
+        let mut learnt_clause = std::mem::take(&mut self.analyzer.learnt_clause);
+        // Literals at the same level are adjacent from the highest level.
+        learnt_clause[1..].sort_unstable_by_key(|lit| std::cmp::Reverse(self.level(lit.var())));
+        let mut shrunk = vec![learnt_clause[0]];
+        let mut begin = 1;
+        while begin < learnt_clause.len() {
+            let level = self.level(learnt_clause[begin].var());
+            let mut end = begin + 1;
+            while end < learnt_clause.len() && self.level(learnt_clause[end].var()) == level {
+                end += 1;
+            }
+            let block = &learnt_clause[begin..end];
+            let uip = if block.len() > 1 {
+                self.block_uip(level, block, abstract_levels, db)
+            } else {
+                None
+            };
+            match uip {
+                Some(uip) => {
+                    if !self.analyzer.seen[uip.var()] {
+                        self.analyzer.seen[uip.var()] = true;
+                        self.analyzer.ccmin_clear.push(uip);
+                    }
+                    shrunk.push(uip);
+                }
+                None => shrunk.extend_from_slice(block),
+            }
+            begin = end;
+        }
+        self.analyzer.learnt_clause = shrunk;
+    }
+
+    /// Returns the negation of the UIP of `block` at `level` if the UIP and literals at lower levels
+    /// in the learnt clause imply every literal in `block`.
+    fn block_uip(
+        &mut self,
+        level: u32,
+        block: &[Lit],
+        abstract_levels: u32,
+        db: &mut ClauseDB,
+    ) -> Option<Lit> {
+        let mut marked = block.to_vec();
+        for lit in block.iter() {
+            self.analyzer.mark[lit.var()] = true;
+        }
+        let mut open = block.len();
+        let mut idx = block
+            .iter()
+            .map(|lit| self.trail_index(lit.var()))
+            .max()
+            .expect("empty block");
+        let uip = 'search: loop {
+            let t = self.trail.stack[idx];
+            idx = idx.saturating_sub(1);
+            if self.level(t.var()) != level || !self.analyzer.mark[t.var()] {
+                continue;
+            }
+            open -= 1;
+            if open == 0 {
+                break Some(!t);
+            }
+            let reason = self.reason(t.var());
+            if let Reason::Card(_) = reason {
+                break 'search None;
+            }
+            let mut i = 0;
+            while let Some(q) = antecedent(db, reason, i) {
+                i += 1;
+                let v = q.var();
+                if self.level(v) == level {
+                    if !self.analyzer.mark[v] {
+                        self.analyzer.mark[v] = true;
+                        marked.push(q);
+                        open += 1;
+                    }
+                } else if self.level(v) > 0
+                    && !self.analyzer.seen[v]
+                    && (!self.reason(v).explained() || !self.redundant(q, abstract_levels, db))
+                {
+                    break 'search None;
+                }
+            }
+        };
+        for lit in marked.iter() {
+            self.analyzer.mark[lit.var()] = false;
+        }
+        uip
+    }
+}
+
+impl Solver {
+    /// Remove a literal `q` from the learnt clause if `learnt_clause[0] v !q` is a binary clause.
+    pub(super) fn strengthen_by_binary(&mut self) {
+        let learnt_clause = &self.vardata.analyzer.learnt_clause;
+        if learnt_clause.len() <= 2 || learnt_clause.len() > BINARY_STRENGTHEN_SIZE {
+            return;
+        }
+        let learnt_clause = std::mem::take(&mut self.vardata.analyzer.learnt_clause);
+        if self.vardata.compute_lbd(learnt_clause.iter()) > BINARY_STRENGTHEN_LBD {
+            self.vardata.analyzer.learnt_clause = learnt_clause;
+            return;
+        }
+        let mut learnt_clause = learnt_clause;
+        for lit in learnt_clause.iter().skip(1) {
+            self.vardata.analyzer.mark[lit.var()] = true;
+        }
+        let uip = learnt_clause[0];
+        let mut removed = 0;
+        for bw in self.watches.lookup_binary(!uip).iter() {
+            let v = bw.other.var();
+            if self.vardata.analyzer.mark[v] && self.vardata.eval(bw.other) == LitBool::True {
+                self.vardata.analyzer.mark[v] = false;
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            learnt_clause.retain(|&lit| lit == uip || self.vardata.analyzer.mark[lit.var()]);
+        }
+        for lit in learnt_clause.iter().skip(1) {
+            self.vardata.analyzer.mark[lit.var()] = false;
+        }
+        self.vardata.analyzer.learnt_clause = learnt_clause;
+    }
+}
@@ -0,0 +1,124 @@
+use crate::types::{bool::LitBool, lit::Lit};
+
+use super::{SatResult, Solver};
+
+/// `Optimum` is the result of `minimize`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Optimum {
+    /// The cost of the model in `models` is the minimum.
+    Found(i64),
+    /// No model exists.
+    Unsat,
+    /// The search is interrupted with the cost of the best model in `models` if any.
+    Unknown(Option<i64>),
+}
+
+impl Solver {
+    /// Returns the sum of the coefficients of the literals of `objective` that are true in `models`.
+    pub fn cost(&self, objective: &[(i64, Lit)]) -> i64 {
+        objective
+            .iter()
+            .filter(|(_, lit)| self.models[lit.var().val() as usize] == lit.true_lbool())
+            .map(|&(a, _)| a)
+            .sum()
+    }
+
+    /// Minimize the sum of the coefficients of the true literals of `objective`
+    /// by linear SAT-UNSAT search, which tightens the bound of the cost after each model
+    /// until no better model exists. `improved` is called with the cost of each better model.
+    /// The bound is kept in the formula after the search.
+    pub fn minimize<F: FnMut(i64)>(
+        &mut self,
+        objective: &[(i64, Lit)],
+        mut improved: F,
+    ) -> Optimum {
+        // The bound is a constraint of the variables of the objective.
+        for &(_, lit) in objective.iter() {
+            self.set_frozen(lit.var(), true);
+        }
+        let negated: Vec<(i64, Lit)> = objective.iter().map(|&(a, lit)| (-a, lit)).collect();
+        let mut best: Option<(i64, Vec<LitBool>)> = None;
+        // the native constraint of the bound
+        let mut bound = None;
+        loop {
+            match self.solve() {
+                SatResult::Sat => {
+                    let cost = self.cost(objective);
+                    debug_assert!(best.as_ref().is_none_or(|&(last, _)| cost < last));
+                    improved(cost);
+                    // The cost of the next model is less than `cost`.
+                    match (bound, best.as_ref()) {
+                        (Some(c), Some(&(last, _))) => self.tighten_card(c, (last - cost) as u64),
+                        _ => bound = self.add_pb_constraint(&negated, 1 - cost),
+                    }
+                    best = Some((cost, self.models.clone()));
+                }
+                SatResult::Unsat => {
+                    return match best {
+                        Some((cost, models)) => {
+                            self.models = models;
+                            Optimum::Found(cost)
+                        }
+                        None => Optimum::Unsat,
+                    };
+                }
+                SatResult::Unknown => {
+                    return match best {
+                        Some((cost, models)) => {
+                            self.models = models;
+                            Optimum::Unknown(Some(cost))
+                        }
+                        None => Optimum::Unknown(None),
+                    };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            testing::{bits_cost, brute_force, models_satisfy, random_kcnf, Rng},
+            Solver,
+        },
+        types::lit::Lit,
+    };
+
+    use super::Optimum;
+
+    #[test]
+    fn test_minimize() {
+        let mut rng = Rng::new();
+        for _ in 0..20 {
+            let num_var = 12;
+            let clauses = random_kcnf(&mut rng, num_var, 30, 3);
+            let objective: Vec<(i64, Lit)> = (0..num_var)
+                .map(|v| {
+                    let a = (rng.next() % 21) as i64 - 5;
+                    (a, Lit::new(v, rng.next().is_multiple_of(2)))
+                })
+                .collect();
+            let mut solver = Solver::new();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let mut costs = Vec::new();
+            let optimum = solver.minimize(&objective, |cost| costs.push(cost));
+
+            // the minimum cost of all models by brute force
+            let expected = brute_force(num_var, &clauses)
+                .map(|bits| bits_cost(bits, &objective))
+                .min();
+            match expected {
+                Some(cost) => {
+                    assert_eq!(optimum, Optimum::Found(cost));
+                    assert_eq!(solver.cost(&objective), cost);
+                    assert!(models_satisfy(&solver.models, &clauses));
+                    assert_eq!(costs.last(), Some(&cost));
+                    assert!(costs.windows(2).all(|w| w[0] > w[1]));
+                }
+                None => assert_eq!(optimum, Optimum::Unsat),
+            }
+        }
+    }
+}
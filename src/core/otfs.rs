@@ -0,0 +1,61 @@
+use crate::{clause::alloc::CRef, types::bool::LitBool};
+
+use super::Solver;
+
+impl Solver {
+    /// Remove the implied literal from a reason clause `cref`
+    /// when the resolvent of conflict analysis with it is a subset of the clause.
+    /// The clause still has two literals at the current level that are watched.
+    pub(super) fn strengthen_reason(&mut self, cref: CRef) {
+        let mut clause = self.db.get_mut(cref);
+        debug_assert!(clause.len() > 3);
+        let (w0, w1) = (clause[0], clause[1]);
+        let last = clause.len() - 1;
+        clause.swap(0, last);
+        self.db.shrink(cref, last);
+        self.watch_highest(cref, w0, w1);
+        self.stats.otfs_strengthened += 1;
+    }
+
+    /// Remove the most recent learnt clauses that are subsumed by the learnt clause.
+    /// Must be called before backtracking because every literal of the learnt clause is false.
+    pub(super) fn eager_subsume(&mut self) {
+        if self.eager_subsume == 0 {
+            return;
+        }
+        let learnt_clause = std::mem::take(&mut self.vardata.analyzer.learnt_clause);
+        for lit in learnt_clause.iter() {
+            self.vardata.analyzer.mark[lit.var()] = true;
+        }
+        let n = self.db.num_learnts();
+        for i in (n.saturating_sub(self.eager_subsume)..n).rev() {
+            let cref = self.db.learnts()[i];
+            let vardata = &self.vardata;
+            let clause = self.db.get_mut(cref);
+            if clause.len() < learnt_clause.len() {
+                continue;
+            }
+            // A false literal of a marked variable is the same literal as in the learnt clause.
+            let common = clause
+                .iter()
+                .filter(|lit| {
+                    vardata.analyzer.mark[lit.var()] && vardata.eval(**lit) == LitBool::False
+                })
+                .count();
+            if common < learnt_clause.len() || self.locked(cref) {
+                continue;
+            }
+            let clause = self.db.get_mut(cref);
+            let (c0, c1) = (clause[0], clause[1]);
+            self.watches.unwatch(c0, cref);
+            self.watches.unwatch(c1, cref);
+            self.db.free(cref);
+            self.db.remove_learnt(cref);
+            self.stats.eager_subsumed += 1;
+        }
+        for lit in learnt_clause.iter() {
+            self.vardata.analyzer.mark[lit.var()] = false;
+        }
+        self.vardata.analyzer.learnt_clause = learnt_clause;
+    }
+}
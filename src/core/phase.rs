@@ -0,0 +1,206 @@
+use crate::{
+    collections::idxvec::VarVec,
+    types::{bool::LitBool, lit::Lit, var::Var},
+};
+
+/// `PhaseConfig` tunes how `Phases` picks and resets the polarity of decisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseConfig {
+    /// Prefer target phases over saved phases. (default true)
+    pub target: bool,
+    /// The base number of conflicts between rephasings. 0 disables rephasing. (default 1000)
+    pub rephase_interval: u64,
+    /// The seed of random rephasing. (default 0)
+    pub seed: u64,
+}
+
+impl Default for PhaseConfig {
+    fn default() -> Self {
+        PhaseConfig {
+            target: true,
+            rephase_interval: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// `Rephase` is a way to overwrite saved phases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rephase {
+    /// All variables are true like the initial phases.
+    Original,
+    /// All variables are false.
+    Inverted,
+    /// Flip every saved phase.
+    Flipping,
+    /// Phases of the trail with the fewest unassigned variables
+    Best,
+    /// Random phases
+    Random,
+}
+
+/// Rephasings cycle through this schedule.
+const SCHEDULE: [Rephase; 8] = [
+    Rephase::Best,
+    Rephase::Original,
+    Rephase::Best,
+    Rephase::Inverted,
+    Rephase::Best,
+    Rephase::Flipping,
+    Rephase::Best,
+    Rephase::Random,
+];
+
+/// `Phases` decides the polarity of a decision variable.
+pub struct Phases {
+    /// phases of variables when they were unassigned
+    pub saved: VarVec<LitBool>,
+    /// phases of the longest conflict-free trail since the last restart
+    target: VarVec<LitBool>,
+    /// phases of the longest conflict-free trail since the last rephasing
+    best: VarVec<LitBool>,
+    target_size: usize,
+    best_size: usize,
+    use_target: bool,
+    interval: u64,
+    /// the number of conflicts at which the next rephasing happens
+    next: u64,
+    rephases: usize,
+    /// the state of a xorshift generator
+    rng: u64,
+}
+
+impl Phases {
+    pub fn new(config: PhaseConfig) -> Phases {
+        Phases {
+            saved: VarVec::new(),
+            target: VarVec::new(),
+            best: VarVec::new(),
+            target_size: 0,
+            best_size: 0,
+            use_target: config.target,
+            interval: config.rephase_interval,
+            next: config.rephase_interval,
+            rephases: 0,
+            rng: config.seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+
+    pub fn new_var(&mut self) {
+        self.saved.push(LitBool::True);
+        self.target.push(LitBool::UnDef);
+        self.best.push(LitBool::UnDef);
+    }
+
+    /// Returns the polarity of the next decision of `v`.
+    pub fn phase(&self, v: Var) -> LitBool {
+        if self.use_target && self.target[v] != LitBool::UnDef {
+            self.target[v]
+        } else {
+            self.saved[v]
+        }
+    }
+
+    /// Update target and best phases with a conflict-free `trail`.
+    pub fn update(&mut self, trail: &[Lit]) {
+        if trail.len() > self.target_size {
+            self.target_size = trail.len();
+            for lit in trail.iter() {
+                self.target[lit.var()] = lit.true_lbool();
+            }
+        }
+        if trail.len() > self.best_size {
+            self.best_size = trail.len();
+            for lit in trail.iter() {
+                self.best[lit.var()] = lit.true_lbool();
+            }
+        }
+    }
+
+    /// Called after a restart.
+    pub fn on_restart(&mut self) {
+        self.target_size = 0;
+    }
+
+    /// Returns true if a rephasing should happen after `conflicts`.
+    pub fn rephase_ready(&self, conflicts: u64) -> bool {
+        self.interval > 0 && conflicts >= self.next
+    }
+
+    /// Overwrite saved phases by the next way in the schedule.
+    /// The interval of rephasings grows arithmetically.
+    pub fn rephase(&mut self, conflicts: u64) -> Rephase {
+        let rephase = SCHEDULE[self.rephases % SCHEDULE.len()];
+        self.rephases += 1;
+        self.next = conflicts + self.interval * (self.rephases as u64 + 1);
+        for v in (0..self.saved.len()).map(Var::from_idx) {
+            self.saved[v] = match rephase {
+                Rephase::Original => LitBool::True,
+                Rephase::Inverted => LitBool::False,
+                Rephase::Flipping if self.saved[v] == LitBool::True => LitBool::False,
+                Rephase::Flipping => LitBool::True,
+                Rephase::Best if self.best[v] != LitBool::UnDef => self.best[v],
+                Rephase::Best => self.saved[v],
+                Rephase::Random => {
+                    self.rng ^= self.rng << 13;
+                    self.rng ^= self.rng >> 7;
+                    self.rng ^= self.rng << 17;
+                    LitBool::from((self.rng & 1) as i8)
+                }
+            };
+            self.target[v] = LitBool::UnDef;
+        }
+        self.target_size = 0;
+        self.best_size = 0;
+        rephase
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_and_best_phases() {
+        let mut phases = Phases::new(PhaseConfig::default());
+        for _ in 0..3 {
+            phases.new_var();
+        }
+        phases.update(&[Lit::new(0, false), Lit::new(1, false)]);
+        assert_eq!(phases.phase(Var(0)), LitBool::False);
+        assert_eq!(phases.phase(Var(2)), LitBool::True);
+        // A shorter trail doesn't overwrite target phases.
+        phases.update(&[Lit::new(0, true)]);
+        assert_eq!(phases.phase(Var(0)), LitBool::False);
+
+        phases.on_restart();
+        phases.update(&[Lit::new(0, true)]);
+        assert_eq!(phases.phase(Var(0)), LitBool::True);
+        assert_eq!(phases.best[Var(0)], LitBool::False);
+    }
+
+    #[test]
+    fn test_rephase() {
+        let mut phases = Phases::new(PhaseConfig {
+            rephase_interval: 10,
+            ..PhaseConfig::default()
+        });
+        for _ in 0..2 {
+            phases.new_var();
+        }
+        phases.update(&[Lit::new(0, false), Lit::new(1, true)]);
+        assert!(!phases.rephase_ready(9));
+        assert!(phases.rephase_ready(10));
+        assert_eq!(phases.rephase(10), Rephase::Best);
+        assert_eq!(phases.saved[Var(0)], LitBool::False);
+        assert_eq!(phases.saved[Var(1)], LitBool::True);
+        // the interval grows after each rephasing.
+        assert!(!phases.rephase_ready(29));
+        assert!(phases.rephase_ready(30));
+        assert_eq!(phases.rephase(30), Rephase::Original);
+        assert_eq!(phases.phase(Var(0)), LitBool::True);
+        phases.rephase(60);
+        assert_eq!(phases.rephase(100), Rephase::Inverted);
+        assert_eq!(phases.phase(Var(1)), LitBool::False);
+    }
+}
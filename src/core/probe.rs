@@ -0,0 +1,233 @@
+use crate::types::{bool::LitBool, lit::Lit, var::Var};
+
+use super::{data::Reason, reduce::ReduceSchedule, Solver};
+
+/// Probing can spend this fraction of the ticks of search since the last one.
+const PROBE_EFFORT: f64 = 0.05;
+/// Probing spends at least this many ticks.
+const PROBE_MIN_EFFORT: u64 = 20_000;
+
+/// `Probe` keeps the schedule and the progress of failed literal probing between restarts.
+pub struct Probe {
+    schedule: ReduceSchedule,
+    /// `ticks` at the end of the last probing
+    ticks: u64,
+    /// The variable that is probed first in the next probing
+    next: usize,
+}
+
+impl Default for Probe {
+    fn default() -> Self {
+        Probe::new(ReduceSchedule::new(2000, 2000))
+    }
+}
+
+impl Probe {
+    pub fn new(schedule: ReduceSchedule) -> Probe {
+        Probe {
+            schedule,
+            ticks: 0,
+            next: 0,
+        }
+    }
+}
+
+impl Solver {
+    /// Probe roots of the binary implication graph if it is scheduled after the current number of conflicts.
+    /// It backtracks to level 0 and returns false if the formula is found unsatisfiable.
+    pub(super) fn probe_if_ready(&mut self) -> bool {
+        let mut probe = match self.probe.take() {
+            Some(probe) if probe.schedule.ready(self.stats.conflicts) => probe,
+            probe => {
+                self.probe = probe;
+                return true;
+            }
+        };
+        probe.schedule.advance(self.stats.conflicts);
+        let ok = self.probe(&mut probe);
+        probe.ticks = self.ticks;
+        self.probe = Some(probe);
+        ok
+    }
+
+    /// Returns a literal of `v` that implies other literals by binary clauses
+    /// but isn't implied by any literal.
+    fn binary_root(&self, v: Var) -> Option<Lit> {
+        [Lit::new(v.val(), true), Lit::new(v.val(), false)]
+            .iter()
+            .copied()
+            .find(|&p| {
+                !self.watches.lookup_binary(p).is_empty()
+                    && self.watches.lookup_binary(!p).is_empty()
+            })
+    }
+
+    fn probe(&mut self, probe: &mut Probe) -> bool {
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        self.stats.probings += 1;
+        let effort = (self.ticks - probe.ticks) as f64 * PROBE_EFFORT;
+        let limit = self.ticks + (effort as u64).max(PROBE_MIN_EFFORT);
+        // Decisions of probing must not overwrite the phases of search.
+        let saved = self.vardata.phases.saved.clone();
+
+        let num_var = self.vardata.num_var();
+        let start = probe.next;
+        let mut ok = true;
+        for i in 0..num_var {
+            if !ok || self.ticks >= limit {
+                break;
+            }
+            let v = Var::from_idx((start + i) % num_var);
+            probe.next = (start + i + 1) % num_var;
+            if self.vardata.define(v) {
+                continue;
+            }
+            if let Some(p) = self.binary_root(v) {
+                ok = self.probe_var(p);
+            }
+        }
+
+        self.vardata.phases.saved = saved;
+        ok
+    }
+
+    /// Probe both polarities of the variable of `p` and learn units from failed literals
+    /// and literals that are implied by both of them.
+    /// Returns false if the formula is found unsatisfiable.
+    fn probe_var(&mut self, p: Lit) -> bool {
+        debug_assert!(self.vardata.trail.decision_level() == 0);
+        let mut units = Vec::new();
+        match self.probe_lit(p) {
+            None => units.push(!p),
+            Some(mut pos) => match self.probe_lit(!p) {
+                None => units.push(p),
+                Some(neg) => {
+                    pos.sort_unstable();
+                    units.extend(neg.into_iter().filter(|lit| pos.binary_search(lit).is_ok()));
+                }
+            },
+        }
+        for lit in units {
+            match self.vardata.eval(lit) {
+                LitBool::True => continue,
+                LitBool::False => return false,
+                _ => {}
+            }
+            self.stats.probed_units += 1;
+            self.vardata.enqueue(lit, Reason::Undef);
+            if self.propagate().is_some() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Propagate `p` at level 1 and returns the literals implied by `p`.
+    /// A literal implied by a clause with at least two literals at level 1
+    /// is also implied by `p` and the hyper-binary resolvent is added.
+    /// `None` means that `p` is a failed literal.
+    fn probe_lit(&mut self, p: Lit) -> Option<Vec<Lit>> {
+        self.vardata.trail.new_decision_level();
+        self.vardata.enqueue(p, Reason::Undef);
+        if self.propagate().is_some() {
+            self.stats.failed_literals += 1;
+            self.vardata.cancel_trail_until(0);
+            return None;
+        }
+        let start = self.vardata.trail.stack_lim[0] + 1;
+        let implied = self.vardata.trail.stack[start..].to_vec();
+        let mut resolvents = Vec::new();
+        for &lit in implied.iter() {
+            if let Reason::Clause(cref) = self.vardata.reason(lit.var()) {
+                let vardata = &self.vardata;
+                let clause = self.db.get_mut(cref);
+                let at_level1 = clause
+                    .iter()
+                    .skip(1)
+                    .filter(|q| vardata.level(q.var()) > 0)
+                    .count();
+                if at_level1 >= 2 {
+                    resolvents.push(lit);
+                }
+            }
+        }
+        self.vardata.cancel_trail_until(0);
+        for lit in resolvents {
+            self.watches.watch_binary(!p, lit, true);
+            self.stats.hyper_binaries += 1;
+        }
+        Some(implied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            reduce::ReduceSchedule,
+            testing::{random_kcnf, solve_and_compare, Rng},
+            SatResult, Solver,
+        },
+        types::{bool::LitBool, lit::Lit},
+    };
+
+    use super::Probe;
+
+    #[test]
+    fn test_probe() {
+        let mut solver = Solver::new();
+        let lits: Vec<Lit> = (0..8).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d, e) = (lits[0], lits[1], lits[2], lits[3], lits[4]);
+        solver.add_clause(&[!a, b]);
+        solver.add_clause(&[!a, c]);
+        solver.add_clause(&[!b, !c, d]);
+        // `a` implies `d` by the ternary clause, so `!a v d` is a hyper-binary resolvent.
+        assert_eq!(solver.binary_root(a.var()), Some(a));
+        assert!(solver.probe_var(a));
+        assert_eq!(solver.stats.hyper_binaries, 1);
+        assert!(solver
+            .watches
+            .lookup_binary(a)
+            .iter()
+            .any(|bw| bw.other == d));
+        assert_eq!(solver.stats.probed_units, 0);
+
+        // `a` is a failed literal.
+        solver.add_clause(&[!d, !b, e]);
+        solver.add_clause(&[!d, !c, !e]);
+        assert!(solver.probe_var(a));
+        assert_eq!(solver.stats.failed_literals, 1);
+        assert_eq!(solver.vardata.eval(a), LitBool::False);
+
+        // `y` is implied by both `x` and `!x`.
+        let (x, y) = (lits[5], lits[6]);
+        solver.add_clause(&[!x, y]);
+        solver.add_clause(&[x, y]);
+        assert!(solver.probe_var(x));
+        assert_eq!(solver.vardata.eval(y), LitBool::True);
+        assert_eq!(solver.stats.probed_units, 2);
+        assert_eq!(solver.solve(), SatResult::Sat);
+    }
+
+    #[test]
+    fn test_solve_with_probing() {
+        let mut rng = Rng::new();
+        let mut probed = 0;
+        for _ in 0..30 {
+            // binary clauses make roots of the binary implication graph.
+            let mut clauses = random_kcnf(&mut rng, 100, 30, 2);
+            clauses.extend(random_kcnf(&mut rng, 100, 380, 3));
+            let mut solver = Solver::new();
+            solver.probe = Some(Probe::new(ReduceSchedule::new(10, 0)));
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let mut expected = Solver::new();
+            expected.probe = None;
+            solve_and_compare(&mut solver, expected, &clauses);
+            probed += solver.stats.hyper_binaries + solver.stats.probed_units;
+        }
+        assert!(probed > 0);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{clause::alloc::CRef, types::bool::LitBool};
+
+use super::{data::Reason, Solver};
+
+/// Learnt clauses whose LBD is less than or equal to `CORE_LBD` are kept forever.
+pub const CORE_LBD: u32 = 2;
+/// Learnt clauses whose LBD is less than or equal to `TIER2_LBD` are kept as long as they are used.
+pub const TIER2_LBD: u32 = 6;
+
+/// `ReduceSchedule` decides when learnt clauses are reduced.
+/// It also schedules other periodic procedures such as vivification.
+pub struct ReduceSchedule {
+    /// The interval is increased by `inc` after each reduction. (default 300)
+    inc: u64,
+    /// The number of conflicts between reductions. (default 2000 at first)
+    interval: u64,
+    /// The number of conflicts at which the next reduction happens.
+    next: u64,
+}
+
+impl Default for ReduceSchedule {
+    fn default() -> Self {
+        ReduceSchedule::new(2000, 300)
+    }
+}
+
+impl ReduceSchedule {
+    pub fn new(first: u64, inc: u64) -> ReduceSchedule {
+        ReduceSchedule {
+            inc,
+            interval: first,
+            next: first,
+        }
+    }
+
+    /// Returns true if a reduction should happen after `conflicts`.
+    pub fn ready(&self, conflicts: u64) -> bool {
+        conflicts >= self.next
+    }
+
+    /// Schedule the next reduction.
+    pub fn advance(&mut self, conflicts: u64) {
+        self.interval += self.inc;
+        self.next = conflicts + self.interval;
+    }
+}
+
+impl Solver {
+    /// A clause is locked if it is the reason of the current assignment.
+    pub(super) fn locked(&mut self, cref: CRef) -> bool {
+        let c0 = self.db.get_mut(cref)[0];
+        self.vardata.eval(c0) == LitBool::True
+            && self.vardata.reason(c0.var()) == Reason::Clause(cref)
+    }
+
+    /// Remove half of the local learnt clauses that have low activities.
+    /// Core clauses are never removed and tier2 clauses that are not used
+    /// since the last reduction are demoted to local clauses.
+    pub(super) fn reduce_db(&mut self) {
+        self.stats.reduce_dbs += 1;
+        let mut locals = Vec::new();
+        for i in 0..self.db.num_learnts() {
+            let cref = self.db.learnts()[i];
+            let locked = self.locked(cref);
+            let mut clause = self.db.get_mut(cref);
+            let used = clause.used();
+            clause.set_used(false);
+            let lbd = clause.lbd();
+            if lbd <= CORE_LBD {
+                continue;
+            }
+            if lbd <= TIER2_LBD {
+                if !used {
+                    clause.set_lbd(TIER2_LBD + 1);
+                }
+                continue;
+            }
+            if !locked {
+                locals.push((clause.activity(), lbd, cref));
+            }
+        }
+        // The worst clauses come first.
+        locals.sort_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .expect("activity is NaN")
+                .then(b.1.cmp(&a.1))
+        });
+        for &(_, _, cref) in locals.iter().take(locals.len() / 2) {
+            self.db.free(cref);
+            self.stats.removed_learnts += 1;
+        }
+        self.db.retain_learnts();
+        self.watches.remove_deleted(&mut self.db);
+        if self.db.needs_gc() {
+            self.garbage_collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReduceSchedule;
+
+    #[test]
+    fn test_reduce_schedule() {
+        let mut schedule = ReduceSchedule::new(100, 10);
+        assert!(!schedule.ready(99));
+        assert!(schedule.ready(100));
+        schedule.advance(100);
+        assert!(!schedule.ready(209));
+        assert!(schedule.ready(210));
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::VecDeque;
+
+use super::luby::LubyRestart;
+
+/// `RestartPolicy` decides when `Solver::search` restarts.
+pub trait RestartPolicy {
+    /// Called after each conflict with the LBD of the learnt clause and the size of the trail.
+    fn on_conflict(&mut self, lbd: u32, trail_size: usize);
+    /// Returns true if the search should restart now.
+    fn should_restart(&mut self) -> bool;
+    /// Called after a restart.
+    fn on_restart(&mut self);
+}
+
+/// `RestartStrategy` selects and tunes a `RestartPolicy`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestartStrategy {
+    /// Restart limits follow the Luby sequence `first * inc^k`.
+    Luby { first: i32, inc: f64 },
+    /// Restart limits grow geometrically `first * factor^k`.
+    Geometric { first: u64, factor: f64 },
+    /// Glucose restarts when the recent LBDs are worse than the global average
+    /// and blocks a restart when the trail is much larger than recent trails.
+    Glucose {
+        lbd_queue: usize,
+        trail_queue: usize,
+        k: f64,
+        r: f64,
+    },
+    /// CaDiCaL restarts when the fast EMA of LBDs exceeds the slow EMA by `margin`.
+    Ema {
+        fast: f64,
+        slow: f64,
+        margin: f64,
+        min_conflicts: u64,
+    },
+}
+
+impl Default for RestartStrategy {
+    fn default() -> Self {
+        RestartStrategy::luby()
+    }
+}
+
+impl RestartStrategy {
+    pub fn luby() -> RestartStrategy {
+        RestartStrategy::Luby {
+            first: 100,
+            inc: 2.5,
+        }
+    }
+    pub fn geometric() -> RestartStrategy {
+        RestartStrategy::Geometric {
+            first: 100,
+            factor: 1.5,
+        }
+    }
+    pub fn glucose() -> RestartStrategy {
+        RestartStrategy::Glucose {
+            lbd_queue: 50,
+            trail_queue: 5000,
+            k: 0.8,
+            r: 1.4,
+        }
+    }
+    pub fn ema() -> RestartStrategy {
+        RestartStrategy::Ema {
+            fast: 0.03,
+            slow: 1e-5,
+            margin: 1.1,
+            min_conflicts: 2,
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn RestartPolicy> {
+        match *self {
+            RestartStrategy::Luby { first, inc } => Box::new(LubyRestart::new(first, inc)),
+            RestartStrategy::Geometric { first, factor } => {
+                Box::new(GeometricRestart::new(first, factor))
+            }
+            RestartStrategy::Glucose {
+                lbd_queue,
+                trail_queue,
+                k,
+                r,
+            } => Box::new(GlucoseRestart::new(lbd_queue, trail_queue, k, r)),
+            RestartStrategy::Ema {
+                fast,
+                slow,
+                margin,
+                min_conflicts,
+            } => Box::new(EmaRestart::new(fast, slow, margin, min_conflicts)),
+        }
+    }
+}
+
+impl std::str::FromStr for RestartStrategy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "luby" => Ok(RestartStrategy::luby()),
+            "geometric" => Ok(RestartStrategy::geometric()),
+            "glucose" => Ok(RestartStrategy::glucose()),
+            "ema" => Ok(RestartStrategy::ema()),
+            _ => Err(format!("unknown restart strategy: {}", s)),
+        }
+    }
+}
+
+/// `GeometricRestart`
+pub struct GeometricRestart {
+    limit: f64,
+    factor: f64,
+    conflicts: u64,
+}
+
+impl GeometricRestart {
+    pub fn new(first: u64, factor: f64) -> GeometricRestart {
+        GeometricRestart {
+            limit: first as f64,
+            factor,
+            conflicts: 0,
+        }
+    }
+}
+
+impl RestartPolicy for GeometricRestart {
+    fn on_conflict(&mut self, _lbd: u32, _trail_size: usize) {
+        self.conflicts += 1;
+    }
+    fn should_restart(&mut self) -> bool {
+        self.conflicts as f64 >= self.limit
+    }
+    fn on_restart(&mut self) {
+        self.limit *= self.factor;
+        self.conflicts = 0;
+    }
+}
+
+/// `BoundedQueue` keeps the sum of the last `cap` values.
+struct BoundedQueue {
+    values: VecDeque<u64>,
+    cap: usize,
+    sum: u64,
+}
+
+impl BoundedQueue {
+    fn new(cap: usize) -> BoundedQueue {
+        BoundedQueue {
+            values: VecDeque::with_capacity(cap),
+            cap,
+            sum: 0,
+        }
+    }
+    fn push(&mut self, x: u64) {
+        if self.values.len() == self.cap {
+            self.sum -= self.values.pop_front().expect("empty queue");
+        }
+        self.values.push_back(x);
+        self.sum += x;
+    }
+    fn full(&self) -> bool {
+        self.values.len() == self.cap
+    }
+    fn avg(&self) -> f64 {
+        self.sum as f64 / self.values.len() as f64
+    }
+    fn clear(&mut self) {
+        self.values.clear();
+        self.sum = 0;
+    }
+}
+
+/// `GlucoseRestart`
+pub struct GlucoseRestart {
+    lbd_queue: BoundedQueue,
+    trail_queue: BoundedQueue,
+    /// Restart if `recent LBD average * k > global LBD average`. (default 0.8)
+    k: f64,
+    /// Block a restart if `trail size > r * recent trail average`. (default 1.4)
+    r: f64,
+    sum_lbd: f64,
+    conflicts: u64,
+}
+
+impl GlucoseRestart {
+    /// Restarts are never blocked before this number of conflicts.
+    const BLOCKING_START: u64 = 10000;
+
+    pub fn new(lbd_queue: usize, trail_queue: usize, k: f64, r: f64) -> GlucoseRestart {
+        GlucoseRestart {
+            lbd_queue: BoundedQueue::new(lbd_queue),
+            trail_queue: BoundedQueue::new(trail_queue),
+            k,
+            r,
+            sum_lbd: 0.0,
+            conflicts: 0,
+        }
+    }
+}
+
+impl RestartPolicy for GlucoseRestart {
+    fn on_conflict(&mut self, lbd: u32, trail_size: usize) {
+        self.conflicts += 1;
+        self.sum_lbd += lbd as f64;
+        self.trail_queue.push(trail_size as u64);
+        // Block a restart when the solver may be close to a model.
+        if self.conflicts > GlucoseRestart::BLOCKING_START
+            && self.lbd_queue.full()
+            && self.trail_queue.full()
+            && trail_size as f64 > self.r * self.trail_queue.avg()
+        {
+            self.lbd_queue.clear();
+        }
+        self.lbd_queue.push(lbd as u64);
+    }
+    fn should_restart(&mut self) -> bool {
+        self.lbd_queue.full()
+            && self.lbd_queue.avg() * self.k > self.sum_lbd / self.conflicts as f64
+    }
+    fn on_restart(&mut self) {
+        self.lbd_queue.clear();
+    }
+}
+
+/// `Ema` is an exponential moving average with bias correction.
+struct Ema {
+    value: f64,
+    alpha: f64,
+    beta: f64,
+    /// `1 - (1 - alpha)^n` to correct the initial bias
+    exp: f64,
+}
+
+impl Ema {
+    fn new(alpha: f64) -> Ema {
+        Ema {
+            value: 0.0,
+            alpha,
+            beta: 1.0 - alpha,
+            exp: 1.0,
+        }
+    }
+    fn update(&mut self, x: f64) {
+        self.value += self.alpha * (x - self.value);
+        self.exp *= self.beta;
+    }
+    fn get(&self) -> f64 {
+        if self.exp >= 1.0 {
+            0.0
+        } else {
+            self.value / (1.0 - self.exp)
+        }
+    }
+}
+
+/// `EmaRestart`
+pub struct EmaRestart {
+    fast: Ema,
+    slow: Ema,
+    /// Restart if `fast > margin * slow`. (default 1.1)
+    margin: f64,
+    /// The minimum number of conflicts between restarts. (default 2)
+    min_conflicts: u64,
+    conflicts: u64,
+}
+
+impl EmaRestart {
+    pub fn new(fast: f64, slow: f64, margin: f64, min_conflicts: u64) -> EmaRestart {
+        EmaRestart {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+            margin,
+            min_conflicts,
+            conflicts: 0,
+        }
+    }
+}
+
+impl RestartPolicy for EmaRestart {
+    fn on_conflict(&mut self, lbd: u32, _trail_size: usize) {
+        self.conflicts += 1;
+        self.fast.update(lbd as f64);
+        self.slow.update(lbd as f64);
+    }
+    fn should_restart(&mut self) -> bool {
+        self.conflicts >= self.min_conflicts && self.fast.get() > self.margin * self.slow.get()
+    }
+    fn on_restart(&mut self) {
+        self.conflicts = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometric_restart() {
+        let mut policy = RestartStrategy::Geometric {
+            first: 10,
+            factor: 2.0,
+        }
+        .build();
+        let mut limits = vec![];
+        let mut conflicts = 0;
+        while limits.len() < 3 {
+            policy.on_conflict(3, 10);
+            conflicts += 1;
+            if policy.should_restart() {
+                limits.push(conflicts);
+                conflicts = 0;
+                policy.on_restart();
+            }
+        }
+        assert_eq!(limits, vec![10, 20, 40]);
+    }
+
+    #[test]
+    fn test_glucose_restart() {
+        let mut policy = GlucoseRestart::new(5, 10, 0.8, 1.4);
+        for _ in 0..100 {
+            policy.on_conflict(4, 100);
+            assert!(!policy.should_restart());
+        }
+        // recent LBDs are much worse than the average.
+        for _ in 0..5 {
+            policy.on_conflict(20, 100);
+        }
+        assert!(policy.should_restart());
+        policy.on_restart();
+        assert!(!policy.should_restart());
+    }
+
+    #[test]
+    fn test_ema_restart() {
+        let mut policy = EmaRestart::new(0.1, 0.001, 1.1, 2);
+        for _ in 0..1000 {
+            policy.on_conflict(4, 100);
+        }
+        assert!(!policy.should_restart());
+        for _ in 0..10 {
+            policy.on_conflict(10, 100);
+        }
+        assert!(policy.should_restart());
+        policy.on_restart();
+        assert!(!policy.should_restart());
+    }
+
+    #[test]
+    fn test_restart_strategy_from_str() {
+        assert_eq!("luby".parse(), Ok(RestartStrategy::luby()));
+        assert_eq!("glucose".parse(), Ok(RestartStrategy::glucose()));
+        assert!("foo".parse::<RestartStrategy>().is_err());
+    }
+}
@@ -0,0 +1,134 @@
+/// `Stats` has counters that are collected while solving.
+#[derive(Debug, Default, Clone)]
+pub struct Stats {
+    pub conflicts: u64,
+    pub decisions: u64,
+    pub propagations: u64,
+    pub restarts: u64,
+    /// the number of decision levels kept by restarts with trail reuse
+    pub reused_levels: u64,
+    /// the number of chronological backtracks instead of backjumps
+    pub chrono_backtracks: u64,
+    /// the number of rephasings of saved phases
+    pub rephases: u64,
+    /// the number of literals removed from learnt clauses by minimization
+    pub minimized_lits: u64,
+    /// the number of reason clauses strengthened by on-the-fly self-subsumption
+    pub otfs_strengthened: u64,
+    /// the number of recent learnt clauses subsumed by a new learnt clause
+    pub eager_subsumed: u64,
+    /// the number of vivification rounds
+    pub vivifications: u64,
+    /// the number of clauses shortened by vivification
+    pub vivified_clauses: u64,
+    /// the number of literals removed by vivification
+    pub vivified_lits: u64,
+    /// the number of probing rounds
+    pub probings: u64,
+    /// the number of failed literals found by probing or unhiding
+    pub failed_literals: u64,
+    /// the number of units learnt by probing
+    pub probed_units: u64,
+    /// the number of hyper-binary resolvents added by probing
+    pub hyper_binaries: u64,
+    /// the number of subsumption rounds over learnt clauses
+    pub subsumptions: u64,
+    /// the number of clauses removed by backward subsumption
+    pub subsumed_clauses: u64,
+    /// the number of duplicate clauses removed
+    pub duplicate_clauses: u64,
+    /// the number of clauses strengthened by self-subsuming resolution
+    pub strengthened_clauses: u64,
+    /// the number of unhiding rounds
+    pub unhides: u64,
+    /// the number of binary clauses removed by transitive reduction
+    pub transitive_binaries: u64,
+    /// the number of hidden tautologies removed
+    pub hidden_tautologies: u64,
+    /// the number of hidden literals removed
+    pub hidden_literals: u64,
+    /// the number of substitutions of equivalent literals
+    pub substitutions: u64,
+    /// the number of variables replaced by their equivalent literals
+    pub substituted_vars: u64,
+    /// the number of blocked clauses removed
+    pub blocked_clauses: u64,
+    /// the number of covered clauses removed
+    pub covered_clauses: u64,
+    /// the number of bounded variable eliminations
+    pub eliminations: u64,
+    /// the number of eliminated variables
+    pub eliminated_vars: u64,
+    /// the number of variables eliminated by their gate definitions
+    pub gates: u64,
+    /// the number of XOR constraints recovered from clauses
+    pub detected_xors: u64,
+    /// the number of literals implied by XOR constraints
+    pub xor_propagations: u64,
+    /// the number of conflicts of XOR constraints
+    pub xor_conflicts: u64,
+    /// the number of cardinality constraints recovered from clauses
+    pub detected_cards: u64,
+    /// the number of literals implied by cardinality and pseudo-Boolean constraints
+    pub card_propagations: u64,
+    /// the number of conflicts of cardinality and pseudo-Boolean constraints
+    pub card_conflicts: u64,
+    /// the number of generators of symmetries broken by lex-leader constraints
+    pub symmetry_generators: u64,
+    /// the number of clauses of lex-leader constraints
+    pub symmetry_clauses: u64,
+    /// the number of `reduce_db` calls
+    pub reduce_dbs: u64,
+    /// the number of learnt clauses removed by `reduce_db`
+    pub removed_learnts: u64,
+    /// the number of garbage collections of the clause arena
+    pub gcs: u64,
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "c conflicts       : {}", self.conflicts)?;
+        writeln!(f, "c decisions       : {}", self.decisions)?;
+        writeln!(f, "c propagations    : {}", self.propagations)?;
+        writeln!(f, "c restarts        : {}", self.restarts)?;
+        writeln!(f, "c reused levels   : {}", self.reused_levels)?;
+        writeln!(f, "c chrono backtrack: {}", self.chrono_backtracks)?;
+        writeln!(f, "c rephases        : {}", self.rephases)?;
+        writeln!(f, "c minimized lits  : {}", self.minimized_lits)?;
+        writeln!(f, "c otfs strengthen : {}", self.otfs_strengthened)?;
+        writeln!(f, "c eager subsumed  : {}", self.eager_subsumed)?;
+        writeln!(f, "c vivifications   : {}", self.vivifications)?;
+        writeln!(f, "c vivified clauses: {}", self.vivified_clauses)?;
+        writeln!(f, "c vivified lits   : {}", self.vivified_lits)?;
+        writeln!(f, "c probings        : {}", self.probings)?;
+        writeln!(f, "c failed literals : {}", self.failed_literals)?;
+        writeln!(f, "c probed units    : {}", self.probed_units)?;
+        writeln!(f, "c hyper binaries  : {}", self.hyper_binaries)?;
+        writeln!(f, "c subsumptions    : {}", self.subsumptions)?;
+        writeln!(f, "c subsumed clauses: {}", self.subsumed_clauses)?;
+        writeln!(f, "c duplicates      : {}", self.duplicate_clauses)?;
+        writeln!(f, "c strengthened    : {}", self.strengthened_clauses)?;
+        writeln!(f, "c unhides         : {}", self.unhides)?;
+        writeln!(f, "c transitive bins : {}", self.transitive_binaries)?;
+        writeln!(f, "c hidden taut     : {}", self.hidden_tautologies)?;
+        writeln!(f, "c hidden lits     : {}", self.hidden_literals)?;
+        writeln!(f, "c substitutions   : {}", self.substitutions)?;
+        writeln!(f, "c substituted vars: {}", self.substituted_vars)?;
+        writeln!(f, "c blocked clauses : {}", self.blocked_clauses)?;
+        writeln!(f, "c covered clauses : {}", self.covered_clauses)?;
+        writeln!(f, "c eliminations    : {}", self.eliminations)?;
+        writeln!(f, "c eliminated vars : {}", self.eliminated_vars)?;
+        writeln!(f, "c gates           : {}", self.gates)?;
+        writeln!(f, "c detected xors   : {}", self.detected_xors)?;
+        writeln!(f, "c xor propagations: {}", self.xor_propagations)?;
+        writeln!(f, "c xor conflicts   : {}", self.xor_conflicts)?;
+        writeln!(f, "c detected cards  : {}", self.detected_cards)?;
+        writeln!(f, "c card props      : {}", self.card_propagations)?;
+        writeln!(f, "c card conflicts  : {}", self.card_conflicts)?;
+        writeln!(f, "c symmetry gens   : {}", self.symmetry_generators)?;
+        writeln!(f, "c symmetry clauses: {}", self.symmetry_clauses)?;
+        writeln!(f, "c reduce dbs      : {}", self.reduce_dbs)?;
+        writeln!(f, "c removed learnts : {}", self.removed_learnts)?;
+        write!(f, "c gcs             : {}", self.gcs)
+    }
+}
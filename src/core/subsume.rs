@@ -0,0 +1,283 @@
+use crate::{
+    clause::alloc::CRef,
+    types::{bool::LitBool, lit::Lit},
+};
+
+use super::{data::Reason, elim::Occurs, reduce::ReduceSchedule, stats::Stats, Solver};
+
+/// Subsumption before search visits at most this many literals.
+pub(super) const SUBSUME_EFFORT: u64 = 10_000_000;
+/// Subsumption of learnt clauses visits at most this many literals.
+const SUBSUME_LEARNT_EFFORT: u64 = 1_000_000;
+
+/// `Subsume` keeps the schedule of subsumption of learnt clauses between restarts.
+pub struct Subsume {
+    schedule: ReduceSchedule,
+}
+
+impl Default for Subsume {
+    fn default() -> Self {
+        Subsume::new(ReduceSchedule::new(3000, 3000))
+    }
+}
+
+impl Subsume {
+    pub fn new(schedule: ReduceSchedule) -> Subsume {
+        Subsume { schedule }
+    }
+}
+
+/// Returns a bit set of the variables of a clause.
+/// `c` can subsume or strengthen `d` only if the signature of `c` is a subset of that of `d`.
+fn signature(lits: &[Lit]) -> u64 {
+    lits.iter()
+        .fold(0, |sig, lit| sig | 1 << (lit.var().val() % 64))
+}
+
+impl Occurs {
+    /// Returns `Some(None)` if the marked clause `c` subsumes `d` and `Some(Some(lit))`
+    /// if `d` without `lit` is the resolvent of `c` and `d`, which subsumes `d`.
+    fn subsumes(&self, c: usize, d: usize) -> Option<Option<Lit>> {
+        let mut common = 0;
+        let mut flipped = None;
+        for &lit in self.clauses[d].iter() {
+            if self.mark[lit] {
+                common += 1;
+            } else if self.mark[!lit] {
+                if flipped.is_some() {
+                    return None;
+                }
+                flipped = Some(lit);
+                common += 1;
+            }
+        }
+        if common == self.clauses[c].len() {
+            Some(flipped)
+        } else {
+            None
+        }
+    }
+
+    /// Remove clauses that are subsumed by other clauses, including duplicates,
+    /// and strengthen clauses by self-subsuming resolution until `limit` literals are visited.
+    /// Strengthened clauses are tried again as subsuming clauses.
+    pub fn subsume(&mut self, stats: &mut Stats, limit: u64) {
+        let mut signatures: Vec<u64> = self.clauses.iter().map(|c| signature(c)).collect();
+        // Shorter clauses are popped first.
+        let mut queue: Vec<usize> = (0..self.clauses.len())
+            .filter(|&idx| !self.removed[idx])
+            .collect();
+        queue.sort_unstable_by_key(|&idx| std::cmp::Reverse(self.clauses[idx].len()));
+        let mut ticks = 0;
+        while let Some(c) = queue.pop() {
+            if ticks > limit {
+                break;
+            }
+            // An empty clause, which units strengthen each other to, is left to the caller.
+            if self.removed[c] || self.clauses[c].is_empty() {
+                continue;
+            }
+            let lits = self.clauses[c].clone();
+            // Every clause that `c` subsumes or strengthens has the variable of any literal of `c`.
+            let mut best = lits[0];
+            let mut fewest = usize::MAX;
+            for &lit in lits.iter() {
+                let num_occs = self.occs(lit).len() + self.occs(!lit).len();
+                if num_occs < fewest {
+                    best = lit;
+                    fewest = num_occs;
+                }
+            }
+            let mut candidates = self.occs(best).to_vec();
+            candidates.extend_from_slice(self.occs(!best));
+            ticks += candidates.len() as u64;
+            for &lit in lits.iter() {
+                self.mark[lit] = true;
+            }
+            for &d in candidates.iter() {
+                if d == c
+                    || self.removed[d]
+                    || self.clauses[d].len() < lits.len()
+                    || signatures[c] & !signatures[d] != 0
+                {
+                    continue;
+                }
+                ticks += self.clauses[d].len() as u64;
+                match self.subsumes(c, d) {
+                    Some(None) => {
+                        if self.clauses[d].len() == lits.len() {
+                            stats.duplicate_clauses += 1;
+                        } else {
+                            stats.subsumed_clauses += 1;
+                        }
+                        self.remove(d);
+                    }
+                    Some(Some(lit)) => {
+                        self.strengthen(d, lit);
+                        signatures[d] = signature(&self.clauses[d]);
+                        stats.strengthened_clauses += 1;
+                        queue.push(d);
+                    }
+                    None => {}
+                }
+            }
+            for &lit in lits.iter() {
+                self.mark[lit] = false;
+            }
+        }
+    }
+}
+
+impl Solver {
+    /// Subsume learnt clauses if it is scheduled after the current number of conflicts.
+    /// It backtracks to level 0 and returns false if the formula is found unsatisfiable.
+    pub(super) fn subsume_if_ready(&mut self) -> bool {
+        let mut subsume = match self.subsume.take() {
+            Some(subsume) if subsume.schedule.ready(self.stats.conflicts) => subsume,
+            subsume => {
+                self.subsume = subsume;
+                return true;
+            }
+        };
+        subsume.schedule.advance(self.stats.conflicts);
+        let ok = self.subsume_learnts();
+        self.subsume = Some(subsume);
+        ok
+    }
+
+    /// Remove learnt clauses subsumed by other learnt clauses and strengthen them
+    /// by self-subsuming resolution. Literals that are false at level 0 are also removed.
+    fn subsume_learnts(&mut self) -> bool {
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        self.stats.subsumptions += 1;
+        let mut occurs = Occurs::new(self.vardata.num_var());
+        let mut candidates: Vec<(CRef, usize)> = Vec::new();
+        for i in 0..self.db.num_learnts() {
+            let cref = self.db.learnts()[i];
+            let clause = self.db.get_mut(cref);
+            if clause.deleted() {
+                continue;
+            }
+            let len = clause.len();
+            let lits: Vec<Lit> = clause.iter().copied().collect();
+            // Satisfied clauses, including reasons at level 0, are left to `reduce_db`.
+            let (satisfied, lits) = self.simplify_clause(&lits);
+            if !satisfied {
+                occurs.add(lits);
+                candidates.push((cref, len));
+            }
+        }
+        occurs.subsume(&mut self.stats, SUBSUME_LEARNT_EFFORT);
+
+        let mut units = Vec::new();
+        for (idx, &(cref, len)) in candidates.iter().enumerate() {
+            let lits = &occurs.clauses[idx];
+            if !occurs.removed[idx] && lits.len() == len {
+                continue;
+            }
+            let clause = self.db.get_mut(cref);
+            let (c0, c1) = (clause[0], clause[1]);
+            self.watches.unwatch(c0, cref);
+            self.watches.unwatch(c1, cref);
+            if occurs.removed[idx] {
+                self.db.free(cref);
+                continue;
+            }
+            match lits.len() {
+                0 => return false,
+                1 => {
+                    self.db.free(cref);
+                    units.push(lits[0]);
+                }
+                2 => {
+                    self.db.free(cref);
+                    self.watches.watch_binary(lits[0], lits[1], true);
+                }
+                _ => {
+                    let mut clause = self.db.get_mut(cref);
+                    for (i, &lit) in lits.iter().enumerate() {
+                        clause[i] = lit;
+                    }
+                    self.db.shrink(cref, lits.len());
+                    self.watches.watch(lits, cref);
+                }
+            }
+        }
+        self.db.retain_learnts();
+        for lit in units {
+            match self.vardata.eval(lit) {
+                LitBool::True => {}
+                LitBool::False => return false,
+                _ => self.vardata.enqueue(lit, Reason::Undef),
+            }
+        }
+        self.propagate().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            elim::Occurs,
+            reduce::ReduceSchedule,
+            stats::Stats,
+            testing::{random_kcnf, solve_and_compare, Rng},
+            Solver,
+        },
+        types::lit::Lit,
+    };
+
+    use super::{Subsume, SUBSUME_EFFORT};
+
+    #[test]
+    fn test_subsume() {
+        let lits: Vec<Lit> = (0..5).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d, e) = (lits[0], lits[1], lits[2], lits[3], lits[4]);
+        let mut occurs = Occurs::new(5);
+        for clause in [
+            vec![a, b],
+            vec![a, b, c],
+            vec![b, a],
+            vec![!a, c, d],
+            vec![a, c, d, e],
+            vec![b, c, d, e],
+        ]
+        .iter()
+        {
+            occurs.add(clause.clone());
+        }
+        let mut stats = Stats::default();
+        occurs.subsume(&mut stats, SUBSUME_EFFORT);
+        // `a v b` subsumes its duplicate and `a v b v c`.
+        assert!(occurs.removed[0] != occurs.removed[2]);
+        assert!(occurs.removed[1]);
+        // `!a v c v d` strengthens `a v c v d v e` to `c v d v e`, which subsumes `b v c v d v e`.
+        assert!(!occurs.removed[3] && !occurs.removed[4]);
+        assert_eq!(occurs.clauses[4], vec![c, d, e]);
+        assert!(occurs.removed[5]);
+        assert_eq!(stats.duplicate_clauses, 1);
+        assert_eq!(stats.subsumed_clauses, 2);
+        assert_eq!(stats.strengthened_clauses, 1);
+    }
+
+    #[test]
+    fn test_solve_with_subsumption() {
+        let mut rng = Rng::new();
+        let mut subsumed = 0;
+        for _ in 0..10 {
+            let clauses = random_kcnf(&mut rng, 100, 426, 3);
+            let mut solver = Solver::new();
+            solver.subsume = Some(Subsume::new(ReduceSchedule::new(10, 10)));
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let mut expected = Solver::new();
+            expected.subsume = None;
+            solve_and_compare(&mut solver, expected, &clauses);
+            subsumed += solver.stats.subsumed_clauses + solver.stats.strengthened_clauses;
+        }
+        assert!(subsumed > 0);
+    }
+}
@@ -0,0 +1,853 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::Range,
+};
+
+use crate::{
+    clause::alloc::CRef,
+    types::{bool::LitBool, lit::Lit, var::Var},
+};
+
+use super::{SatResult, Solver};
+
+/// The effort of the automorphism search in visited edges and copied vertices.
+const SEARCH_EFFORT: u64 = 100_000_000;
+
+/// `Symmetry` keeps generators of symmetries that are broken before the first search.
+pub struct Symmetry {
+    /// search generators by automorphisms of the graph of clauses and literals
+    detect: bool,
+    /// generators given by `add_symmetry` as pairs of literals and their images
+    generators: Vec<Vec<(Lit, Lit)>>,
+    /// Symmetries are broken only in the first `solve`.
+    done: bool,
+    /// assumed in each `solve` and falsified to disable the lex-leader constraints
+    selector: Option<Lit>,
+    /// variables moved by the broken symmetries
+    moved: Vec<bool>,
+    /// the selector and the variables added by the lex-leader constraints
+    aux: Range<usize>,
+}
+
+impl Symmetry {
+    pub fn new(detect: bool) -> Symmetry {
+        Symmetry {
+            detect,
+            generators: Vec::new(),
+            done: false,
+            selector: None,
+            moved: Vec::new(),
+            aux: 0..0,
+        }
+    }
+}
+
+/// A colored graph whose vertices are literals and clauses.
+/// A literal is adjacent to its negation and the clauses that contain it.
+struct Graph {
+    /// sorted neighbors
+    adj: Vec<Vec<u32>>,
+    colors: Vec<u32>,
+}
+
+impl Graph {
+    /// Returns true if `perm` maps the neighbors of each vertex to the neighbors of its image.
+    /// Edges between fixed vertices are mapped to themselves, so only moved vertices are checked.
+    fn is_automorphism(&self, perm: &[u32], ticks: &mut u64) -> bool {
+        let mut image = Vec::new();
+        for (a, &b) in perm.iter().enumerate() {
+            if a as u32 == b {
+                continue;
+            }
+            *ticks += self.adj[a].len() as u64;
+            image.clear();
+            image.extend(self.adj[a].iter().map(|&w| perm[w as usize]));
+            image.sort_unstable();
+            if image != self.adj[b as usize] {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// An ordered partition of vertices, whose cells are ranges of `order`.
+#[derive(Clone)]
+struct Partition {
+    order: Vec<u32>,
+    /// the position of each vertex in `order`
+    pos: Vec<u32>,
+    /// the first position of the cell of each position
+    start: Vec<u32>,
+    /// the end of the cell that starts at each position
+    end: Vec<u32>,
+    num_cells: usize,
+}
+
+/// Buffers of `Partition::refine`.
+struct Refiner {
+    counts: Vec<u32>,
+    touched: Vec<u32>,
+    queued: Vec<bool>,
+    queue: VecDeque<u32>,
+}
+
+impl Partition {
+    /// Returns the partition into cells of the same colors.
+    fn new(colors: &[u32]) -> Partition {
+        let n = colors.len();
+        let mut order: Vec<u32> = (0..n as u32).collect();
+        order.sort_by_key(|&v| colors[v as usize]);
+        let mut pos = vec![0; n];
+        let mut start = vec![0; n];
+        let mut end = vec![0; n];
+        let mut num_cells = 0;
+        for i in 0..n {
+            pos[order[i] as usize] = i as u32;
+            if i == 0 || colors[order[i] as usize] != colors[order[i - 1] as usize] {
+                num_cells += 1;
+                start[i] = i as u32;
+            } else {
+                start[i] = start[i - 1];
+            }
+            end[start[i] as usize] = i as u32 + 1;
+        }
+        Partition {
+            order,
+            pos,
+            start,
+            end,
+            num_cells,
+        }
+    }
+
+    fn cell(&self, v: u32) -> u32 {
+        self.start[self.pos[v as usize] as usize]
+    }
+
+    fn discrete(&self) -> bool {
+        self.num_cells == self.order.len()
+    }
+
+    /// Returns the first cell that has more than one vertex.
+    fn target_cell(&self) -> Option<u32> {
+        let mut i = 0;
+        while i < self.order.len() {
+            let end = self.end[i] as usize;
+            if end - i > 1 {
+                return Some(i as u32);
+            }
+            i = end;
+        }
+        None
+    }
+
+    /// Move `v` to a new cell before the rest of its cell. Returns the new cell.
+    fn individualize(&mut self, v: u32) -> u32 {
+        let cell = self.cell(v);
+        let (s, e) = (cell as usize, self.end[cell as usize] as usize);
+        let p = self.pos[v as usize] as usize;
+        let u = self.order[s];
+        self.order.swap(s, p);
+        self.pos[u as usize] = p as u32;
+        self.pos[v as usize] = s as u32;
+        for i in s + 1..e {
+            self.start[i] = s as u32 + 1;
+        }
+        self.end[s] = s as u32 + 1;
+        self.end[s + 1] = e as u32;
+        self.num_cells += 1;
+        cell
+    }
+
+    /// Split cells by the numbers of their neighbors in each splitter
+    /// until the partition is equitable. Cells are split in the same order for isomorphic
+    /// partitions, so an automorphism maps the refined partitions as well.
+    fn refine(&mut self, graph: &Graph, splitters: &[u32], refiner: &mut Refiner, ticks: &mut u64) {
+        for &s in splitters.iter() {
+            refiner.queue.push_back(s);
+            refiner.queued[s as usize] = true;
+        }
+        while let Some(s) = refiner.queue.pop_front() {
+            refiner.queued[s as usize] = false;
+            for i in s..self.end[s as usize] {
+                let u = self.order[i as usize];
+                *ticks += graph.adj[u as usize].len() as u64;
+                for &w in graph.adj[u as usize].iter() {
+                    if refiner.counts[w as usize] == 0 {
+                        refiner.touched.push(w);
+                    }
+                    refiner.counts[w as usize] += 1;
+                }
+            }
+            let mut touched = std::mem::take(&mut refiner.touched);
+            touched.sort_unstable_by_key(|&w| (self.cell(w), refiner.counts[w as usize]));
+            let mut i = 0;
+            while i < touched.len() {
+                let cell = self.cell(touched[i]);
+                let mut j = i + 1;
+                while j < touched.len() && self.cell(touched[j]) == cell {
+                    j += 1;
+                }
+                self.split(cell, &touched[i..j], refiner);
+                i = j;
+            }
+            for &w in touched.iter() {
+                refiner.counts[w as usize] = 0;
+            }
+            touched.clear();
+            refiner.touched = touched;
+        }
+    }
+
+    /// Split `cell` by the counts of `touched` vertices sorted by their counts.
+    /// Untouched vertices come first, followed by touched vertices in the ascending order of counts.
+    fn split(&mut self, cell: u32, touched: &[u32], refiner: &mut Refiner) {
+        let counts = &refiner.counts;
+        let (s, e) = (cell as usize, self.end[cell as usize] as usize);
+        let m = touched.len();
+        let first = counts[touched[0] as usize];
+        if e - s == 1 || (m == e - s && counts[touched[m - 1] as usize] == first) {
+            return;
+        }
+        for (k, &w) in touched.iter().enumerate() {
+            let target = e - m + k;
+            let p = self.pos[w as usize] as usize;
+            let u = self.order[target];
+            self.order.swap(target, p);
+            self.pos[u as usize] = p as u32;
+            self.pos[w as usize] = target as u32;
+        }
+        let mut starts = Vec::new();
+        if m < e - s {
+            starts.push(s);
+        }
+        for k in 0..m {
+            if k == 0 || counts[touched[k] as usize] != counts[touched[k - 1] as usize] {
+                starts.push(e - m + k);
+            }
+        }
+        for (k, &f) in starts.iter().enumerate() {
+            let fe = starts.get(k + 1).copied().unwrap_or(e);
+            if k > 0 {
+                for p in f..fe {
+                    self.start[p] = f as u32;
+                }
+            }
+            self.end[f] = fe as u32;
+        }
+        self.num_cells += starts.len() - 1;
+        // A cell that is waiting to split others is replaced by its fragments.
+        // Otherwise, splitting by all but the largest fragment is enough.
+        let skip = if refiner.queued[s] {
+            Some(s)
+        } else {
+            starts
+                .iter()
+                .copied()
+                .max_by_key(|&f| (self.end[f] as usize - f, std::cmp::Reverse(f)))
+        };
+        for &f in starts.iter() {
+            if Some(f) != skip && !refiner.queued[f] {
+                refiner.queued[f] = true;
+                refiner.queue.push_back(f as u32);
+            }
+        }
+    }
+}
+
+/// Returns the representative of the orbit of `v`.
+fn find(parent: &mut [u32], v: u32) -> u32 {
+    let mut root = v;
+    while parent[root as usize] != root {
+        root = parent[root as usize];
+    }
+    let mut v = v;
+    while parent[v as usize] != root {
+        let next = parent[v as usize];
+        parent[v as usize] = root;
+        v = next;
+    }
+    root
+}
+
+/// Search generators of automorphisms of `graph` by individualization and refinement.
+/// The first path individualizes the first vertex of the first non-singleton cell until
+/// the partition is discrete. From the deepest level, each other vertex of the cell at the level
+/// is individualized instead and the path continues in the same cells as the first path.
+/// If its leaf is mapped from the first leaf by an automorphism, it is a generator.
+/// Vertices in the same orbit of the generators found so far are skipped.
+/// The search is incomplete, but every returned permutation is an automorphism.
+fn search_generators(graph: &Graph) -> Vec<Vec<u32>> {
+    let n = graph.colors.len();
+    let mut refiner = Refiner {
+        counts: vec![0; n],
+        touched: Vec::new(),
+        queued: vec![false; n],
+        queue: VecDeque::new(),
+    };
+    let mut ticks = 0;
+    let mut partition = Partition::new(&graph.colors);
+    let mut cells = Vec::new();
+    let mut cell = 0;
+    while (cell as usize) < n {
+        cells.push(cell);
+        cell = partition.end[cell as usize];
+    }
+    partition.refine(graph, &cells, &mut refiner, &mut ticks);
+    // the partitions, the target cells and the individualized vertices of the first path
+    let mut path: Vec<(Partition, u32, u32)> = Vec::new();
+    while let Some(cell) = partition.target_cell() {
+        ticks += n as u64;
+        if ticks > SEARCH_EFFORT {
+            return Vec::new();
+        }
+        let v = partition.order[cell as usize];
+        path.push((partition.clone(), cell, v));
+        let splitter = partition.individualize(v);
+        partition.refine(graph, &[splitter], &mut refiner, &mut ticks);
+    }
+    let leaf = partition.order;
+
+    let mut generators = Vec::new();
+    let mut orbits: Vec<u32> = (0..n as u32).collect();
+    for level in (0..path.len()).rev() {
+        let (saved, cell, v) = &path[level];
+        let (s, e) = (*cell as usize, saved.end[*cell as usize] as usize);
+        let mut candidates = saved.order[s..e].to_vec();
+        candidates.sort_unstable();
+        for w in candidates {
+            if w == *v || find(&mut orbits, w) == find(&mut orbits, *v) {
+                continue;
+            }
+            if ticks > SEARCH_EFFORT {
+                return generators;
+            }
+            // Follow the first path after individualizing `w` instead of `v`.
+            ticks += n as u64;
+            let mut q = saved.clone();
+            let splitter = q.individualize(w);
+            q.refine(graph, &[splitter], &mut refiner, &mut ticks);
+            let mut matched = true;
+            for (next, cell, u) in path[level + 1..].iter() {
+                if q.num_cells != next.num_cells
+                    || q.start[*cell as usize] != *cell
+                    || q.end[*cell as usize] != next.end[*cell as usize]
+                {
+                    matched = false;
+                    break;
+                }
+                let u = if q.cell(*u) == *cell {
+                    *u
+                } else {
+                    q.order[*cell as usize]
+                };
+                let splitter = q.individualize(u);
+                q.refine(graph, &[splitter], &mut refiner, &mut ticks);
+            }
+            if !matched || !q.discrete() {
+                continue;
+            }
+            let mut perm = vec![0; n];
+            for (i, &a) in leaf.iter().enumerate() {
+                perm[a as usize] = q.order[i];
+            }
+            if graph.is_automorphism(&perm, &mut ticks) {
+                for (a, &b) in perm.iter().enumerate() {
+                    let (ra, rb) = (find(&mut orbits, a as u32), find(&mut orbits, b));
+                    orbits[ra as usize] = rb;
+                }
+                generators.push(perm);
+            }
+        }
+    }
+    generators
+}
+
+impl Solver {
+    /// Add a symmetry of the formula, which is a permutation of literals given as pairs
+    /// of literals and their images. The negation of a literal is mapped to the negation
+    /// of its image, and unlisted literals are mapped to themselves.
+    /// Symmetries are checked against the formula and broken in the first `solve`,
+    /// so this has to be called before it.
+    pub fn add_symmetry(&mut self, generator: &[(Lit, Lit)]) {
+        self.symmetry.generators.push(generator.to_vec());
+    }
+
+    /// Break symmetries of the formula by lex-leader constraints in the first call.
+    /// Generators are given by `add_symmetry` or found by the automorphism search.
+    /// Frozen, assumed and assigned variables and variables of native constraints
+    /// are fixed by the symmetries.
+    /// The constraints are guarded by a selector, which is assumed until
+    /// a later constraint or assumption mentions a moved variable.
+    /// Returns false if the formula is found unsatisfiable.
+    pub(super) fn break_symmetries(&mut self) -> bool {
+        if self.symmetry.done || (!self.symmetry.detect && self.symmetry.generators.is_empty()) {
+            self.symmetry.done = true;
+            return true;
+        }
+        self.symmetry.done = true;
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        let num_var = self.vardata.num_var();
+        let mut fixed: Vec<bool> = (0..num_var)
+            .map(Var::from_idx)
+            .map(|v| {
+                self.elim.frozen[v]
+                    || self.vardata.define(v)
+                    || self.elim.removed(v)
+                    || self.vardata.lbool(v) != LitBool::UnDef
+            })
+            .collect();
+        for lit in self.assumptions.iter() {
+            fixed[lit.var().val() as usize] = true;
+        }
+        for v in self.gauss.vars().chain(self.cards.vars()) {
+            fixed[v.val() as usize] = true;
+        }
+        let mut clauses = self.irredundant_clauses();
+        // Duplicate clauses only add automorphisms that fix all literals.
+        clauses.sort();
+        clauses.dedup();
+
+        // literal maps indexed by literals
+        let mut generators: Vec<Vec<Lit>> = Vec::new();
+        let given = std::mem::take(&mut self.symmetry.generators);
+        if !given.is_empty() {
+            let set: HashSet<&Vec<Lit>> = clauses.iter().collect();
+            for generator in given.iter() {
+                let map = match literal_map(num_var, generator) {
+                    Some(map) => map,
+                    None => continue,
+                };
+                let moves_fixed =
+                    (0..num_var).any(|v| fixed[v] && map[2 * v] != Lit::new(v as u32, true));
+                let symmetric = clauses.iter().all(|clause| {
+                    let mut image: Vec<Lit> =
+                        clause.iter().map(|lit| map[lit.val() as usize]).collect();
+                    image.sort();
+                    set.contains(&image)
+                });
+                if !moves_fixed && symmetric {
+                    generators.push(map);
+                }
+            }
+        }
+        if self.symmetry.detect {
+            let graph = symmetry_graph(num_var, &clauses, &fixed);
+            for perm in search_generators(&graph) {
+                generators.push(
+                    perm[..2 * num_var]
+                        .iter()
+                        .map(|&lit| Lit::from_idx(lit as usize))
+                        .collect(),
+                );
+            }
+        }
+        generators.retain(|map| {
+            map.iter()
+                .enumerate()
+                .any(|(x, lit)| lit.val() as usize != x)
+        });
+        if generators.is_empty() {
+            return true;
+        }
+        self.stats.symmetry_generators += generators.len() as u64;
+        let selector = Lit::new(self.new_var().val(), true);
+        self.set_frozen(selector.var(), true);
+        for map in generators.iter() {
+            self.add_lex_leader(map, selector);
+        }
+        // Moved variables are marked after the lex-leader constraints are added.
+        self.symmetry.moved = vec![false; num_var];
+        for map in generators.iter() {
+            for v in 0..num_var {
+                if map[2 * v] != Lit::new(v as u32, true) {
+                    self.symmetry.moved[v] = true;
+                }
+            }
+        }
+        self.symmetry.selector = Some(selector);
+        self.symmetry.aux = num_var..self.vardata.num_var();
+        self.result != SatResult::Unsat
+    }
+
+    /// Returns the selector of the lex-leader constraints to assume.
+    pub(super) fn symmetry_selector(&self) -> Option<Lit> {
+        self.symmetry.selector
+    }
+
+    /// Remove the lex-leader constraints for good if `lits` mention a variable
+    /// moved by the broken symmetries, because a constraint or an assumption over it
+    /// may not be symmetric. They are removed as well if `lits` mention the selector
+    /// or an auxiliary variable, which becomes a new variable of the caller.
+    pub(super) fn retire_symmetries(&mut self, lits: &[Lit]) {
+        let selector = match self.symmetry.selector {
+            Some(selector) => selector,
+            None => return,
+        };
+        let (moved, aux) = (&self.symmetry.moved, &self.symmetry.aux);
+        if !lits.iter().any(|lit| {
+            let idx = lit.var().val() as usize;
+            moved.get(idx) == Some(&true) || aux.contains(&idx)
+        }) {
+            return;
+        }
+        self.symmetry.selector = None;
+        self.vardata.cancel_trail_until(0);
+        let s = selector.var();
+        if self.vardata.lbool(s) != LitBool::UnDef {
+            // `!selector` is implied at level 0, but the lex-leader constraints
+            // keep a model of a satisfiable formula.
+            self.result = SatResult::Unsat;
+            return;
+        }
+        // Every clause derived from the lex-leader constraints contains `!selector`,
+        // because `selector` is only assumed. The rest of the formula is the same as
+        // if `selector` was false, so the auxiliary variables are in no constraint.
+        let crefs: Vec<CRef> = self
+            .db
+            .clauses()
+            .iter()
+            .chain(self.db.learnts())
+            .copied()
+            .collect();
+        for cref in crefs {
+            let clause = self.db.get_mut(cref);
+            if !clause.deleted() && clause.iter().any(|lit| lit.var() == s) {
+                self.db.free(cref);
+            }
+        }
+        self.db.retain_clauses();
+        self.db.retain_learnts();
+        self.watches.remove_deleted(&mut self.db);
+        self.watches
+            .retain_binary(|p, bw| p.var() != s && bw.other.var() != s);
+        self.elim.forget(s);
+        self.cards.uncount(self.vardata.trail.peek_head);
+        self.cards.remove_var(s);
+        self.set_frozen(s, false);
+    }
+
+    /// Returns the irredundant clauses simplified by the assignments at level 0.
+    fn irredundant_clauses(&mut self) -> Vec<Vec<Lit>> {
+        let mut clauses = Vec::new();
+        for i in 0..self.db.num_clauses() {
+            let cref = self.db.clauses()[i];
+            let clause = self.db.get_mut(cref);
+            if clause.deleted() {
+                continue;
+            }
+            let lits: Vec<Lit> = clause.iter().copied().collect();
+            let (satisfied, lits) = self.simplify_clause(&lits);
+            if !satisfied {
+                clauses.push(lits);
+            }
+        }
+        for v in (0..self.vardata.num_var()).map(Var::from_idx) {
+            for p in [Lit::new(v.val(), true), Lit::new(v.val(), false)].iter() {
+                // `bw` watches `!p v bw.other`, which is visited twice.
+                for bw in self.watches.lookup_binary(*p).iter() {
+                    if !bw.learnt && !*p < bw.other {
+                        let (satisfied, lits) = self.simplify_clause(&[!*p, bw.other]);
+                        if !satisfied {
+                            clauses.push(lits);
+                        }
+                    }
+                }
+            }
+        }
+        clauses
+    }
+
+    /// Add the lex-leader constraint that the values of variables in order are
+    /// lexicographically at most the values of their images by `map`, where false is less than true.
+    /// `e` of each position is implied if the values of all previous positions are equal.
+    /// Each clause is satisfied if `selector` is false.
+    fn add_lex_leader(&mut self, map: &[Lit], selector: Lit) {
+        let mut pairs: Vec<(Lit, Lit)> = Vec::new();
+        let mut seen: HashSet<(Lit, Lit)> = HashSet::new();
+        for v in 0..map.len() as u32 / 2 {
+            let x = Lit::new(v, true);
+            let y = map[x.val() as usize];
+            // `x` and `y` are equal if the previous pair of `y` and `x` is equal.
+            if y == x || seen.contains(&(y, x)) || seen.contains(&(!y, !x)) {
+                continue;
+            }
+            seen.insert((x, y));
+            pairs.push((x, y));
+            if y == !x {
+                // `x` must be false and the pairs are never equal after this.
+                break;
+            }
+        }
+        let mut e: Option<Lit> = None;
+        for (i, &(x, y)) in pairs.iter().enumerate() {
+            let with_e = |lits: &[Lit]| -> Vec<Lit> {
+                lits.iter()
+                    .copied()
+                    .chain(e.map(|e| !e))
+                    .chain(Some(!selector))
+                    .collect()
+            };
+            self.add_clause(&with_e(&[!x, y]));
+            self.stats.symmetry_clauses += 1;
+            if i + 1 < pairs.len() {
+                let next = Lit::new(self.new_var().val(), true);
+                self.add_clause(&with_e(&[!x, next]));
+                self.add_clause(&with_e(&[y, next]));
+                self.stats.symmetry_clauses += 2;
+                e = Some(next);
+            }
+        }
+    }
+}
+
+/// Returns the map of literals of `generator`, or `None` if it isn't a permutation
+/// of literals of `num_var` variables that commutes with negation.
+fn literal_map(num_var: usize, generator: &[(Lit, Lit)]) -> Option<Vec<Lit>> {
+    let mut map: Vec<Lit> = (0..2 * num_var).map(Lit::from_idx).collect();
+    let mut mapped = vec![false; 2 * num_var];
+    for &(a, b) in generator.iter() {
+        if a.var().val() as usize >= num_var || b.var().val() as usize >= num_var {
+            return None;
+        }
+        for &(a, b) in [(a, b), (!a, !b)].iter() {
+            let a = a.val() as usize;
+            if mapped[a] && map[a] != b {
+                return None;
+            }
+            mapped[a] = true;
+            map[a] = b;
+        }
+    }
+    let mut images = vec![false; 2 * num_var];
+    for lit in map.iter() {
+        if std::mem::replace(&mut images[lit.val() as usize], true) {
+            return None;
+        }
+    }
+    Some(map)
+}
+
+/// Returns the graph of `clauses`. Literals of variables that aren't `fixed` have the same color,
+/// clauses have another color and each literal of `fixed` variables has its own color.
+fn symmetry_graph(num_var: usize, clauses: &[Vec<Lit>], fixed: &[bool]) -> Graph {
+    let n = 2 * num_var + clauses.len();
+    let mut adj: Vec<Vec<u32>> = vec![Vec::new(); n];
+    let mut colors = vec![1; n];
+    for x in 0..2 * num_var {
+        adj[x].push(x as u32 ^ 1);
+        colors[x] = if fixed[x / 2] { 2 + x as u32 } else { 0 };
+    }
+    for (i, clause) in clauses.iter().enumerate() {
+        let c = 2 * num_var + i;
+        for lit in clause.iter() {
+            adj[c].push(lit.val());
+            adj[lit.val() as usize].push(c as u32);
+        }
+    }
+    for neighbors in adj.iter_mut() {
+        neighbors.sort_unstable();
+    }
+    Graph { adj, colors }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            config::Config,
+            optimize::Optimum,
+            testing::{bits_cost, brute_force, models_satisfy, Rng},
+            SatResult, Solver,
+        },
+        types::{bool::LitBool, lit::Lit},
+    };
+
+    fn pigeonhole(pigeons: u32, holes: u32) -> Vec<Vec<Lit>> {
+        let x = |p: u32, h: u32| Lit::new(p * holes + h, true);
+        let mut clauses: Vec<Vec<Lit>> = (0..pigeons)
+            .map(|p| (0..holes).map(|h| x(p, h)).collect())
+            .collect();
+        for h in 0..holes {
+            for p in 0..pigeons {
+                for q in p + 1..pigeons {
+                    clauses.push(vec![!x(p, h), !x(q, h)]);
+                }
+            }
+        }
+        clauses
+    }
+
+    #[test]
+    fn test_search_generators() {
+        let clauses = pigeonhole(6, 5);
+        let mut solver = Solver::with_config(Config {
+            symmetry: true,
+            ..Config::default()
+        });
+        clauses.iter().for_each(|clause| solver.add_clause(clause));
+        let num_var = solver.num_var();
+        let graph = super::symmetry_graph(num_var, &clauses, &vec![false; num_var]);
+        let generators = super::search_generators(&graph);
+        assert!(!generators.is_empty());
+        let mut ticks = 0;
+        assert!(generators
+            .iter()
+            .all(|perm| graph.is_automorphism(perm, &mut ticks)));
+        assert_eq!(solver.solve(), SatResult::Unsat);
+        assert!(solver.stats.symmetry_generators > 0);
+    }
+
+    #[test]
+    fn test_retire_symmetries() {
+        let x0 = Lit::new(0, true);
+        let x1 = Lit::new(1, true);
+
+        // a clause over a variable moved by a given symmetry
+        let mut solver = Solver::with_config(Config {
+            symmetry: true,
+            ..Config::default()
+        });
+        solver.add_clause(&[x0, x1]);
+        solver.add_symmetry(&[(x0, x1), (x1, x0)]);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert!(solver.stats.symmetry_generators > 0);
+        solver.add_clause(&[!x1]);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert_eq!(solver.models[0], LitBool::True);
+
+        // an assumption over a variable moved by a found symmetry
+        let mut solver = Solver::with_config(Config {
+            symmetry: true,
+            ..Config::default()
+        });
+        solver.add_clause(&[x0, x1]);
+        solver.add_clause(&[!x0, !x1]);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert!(solver.stats.symmetry_generators > 0);
+        assert_eq!(solver.solve_with_assumptions(&[x0]), SatResult::Sat);
+        assert_eq!(solver.models[0], LitBool::True);
+        assert_eq!(solver.solve_with_assumptions(&[x1]), SatResult::Sat);
+        assert_eq!(solver.models[1], LitBool::True);
+        assert_eq!(solver.solve_with_assumptions(&[x0, x1]), SatResult::Unsat);
+        assert!(!solver.failed_assumptions.is_empty());
+    }
+
+    #[test]
+    fn test_new_vars_after_symmetry() {
+        let mut rng = Rng::new();
+        for _ in 0..200 {
+            // Clauses closed under swapping x0 with x1, x2 with x3, and so on.
+            let num_var = 8;
+            let image = |lit: Lit| Lit::new(lit.var().0 ^ 1, lit.pos());
+            let mut clauses: Vec<Vec<Lit>> = Vec::new();
+            for _ in 0..6 {
+                let clause: Vec<Lit> = (0..3).map(|_| rng.lit(num_var)).collect();
+                clauses.push(clause.iter().map(|&lit| image(lit)).collect());
+                clauses.push(clause);
+            }
+            let mut solver = Solver::with_config(Config {
+                symmetry: true,
+                ..Config::default()
+            });
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let expected = brute_force(num_var, &clauses).next().is_some();
+            assert_eq!(solver.solve() == SatResult::Sat, expected);
+
+            // Later clauses and assumptions mention variables which the caller has not created,
+            // and which the solver may use for the lex-leader constraints.
+            let total = 2 * num_var;
+            for _ in 0..4 {
+                for _ in 0..rng.next() % 3 {
+                    let clause: Vec<Lit> =
+                        (0..1 + rng.next() % 3).map(|_| rng.lit(total)).collect();
+                    solver.add_clause(&clause);
+                    clauses.push(clause);
+                }
+                let assumptions: Vec<Lit> = (0..rng.next() % 3).map(|_| rng.lit(total)).collect();
+                let mut assumed = clauses.clone();
+                assumed.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = brute_force(total, &assumed).next().is_some();
+                let result = solver.solve_with_assumptions(&assumptions);
+                assert_eq!(result == SatResult::Sat, expected);
+                if result == SatResult::Sat {
+                    assert!(models_satisfy(&solver.models, &assumed));
+                } else {
+                    let mut core = clauses.clone();
+                    core.extend(solver.failed_assumptions.iter().map(|&lit| vec![lit]));
+                    assert!(solver
+                        .failed_assumptions
+                        .iter()
+                        .all(|lit| assumptions.contains(lit)));
+                    assert!(brute_force(total, &core).next().is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_with_symmetry() {
+        let mut rng = Rng::new();
+        for i in 0..100 {
+            // Clauses closed under a random permutation of variables with negations.
+            let num_var = 12;
+            let mut perm: Vec<u32> = (0..num_var).collect();
+            for j in (1..perm.len()).rev() {
+                perm.swap(j, (rng.next() % (j as u64 + 1)) as usize);
+            }
+            let flips: Vec<bool> = (0..num_var).map(|_| rng.next().is_multiple_of(4)).collect();
+            let image = |lit: Lit| {
+                let v = lit.var().0 as usize;
+                Lit::new(perm[v], lit.pos() != flips[v])
+            };
+            let mut clauses: Vec<Vec<Lit>> = Vec::new();
+            for _ in 0..4 {
+                let first: Vec<Lit> = (0..3).map(|_| rng.lit(num_var)).collect();
+                let mut clause = first.clone();
+                loop {
+                    clauses.push(clause.clone());
+                    clause = clause.iter().map(|&lit| image(lit)).collect();
+                    if clause == first {
+                        break;
+                    }
+                }
+            }
+            let generator: Vec<(Lit, Lit)> = (0..num_var)
+                .map(|v| (Lit::new(v, true), image(Lit::new(v, true))))
+                .collect();
+            let objective: Vec<(i64, Lit)> = (0..num_var)
+                .map(|v| ((v % 3 + 1) as i64, Lit::new(v, true)))
+                .collect();
+            let expected = brute_force(num_var, &clauses)
+                .map(|bits| bits_cost(bits, &objective))
+                .min();
+
+            let mut solver = Solver::with_config(Config {
+                symmetry: i % 2 == 0,
+                ..Config::default()
+            });
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            solver.add_symmetry(&generator);
+            // an invalid generator is ignored
+            solver.add_symmetry(&[(Lit::new(0, true), Lit::new(1, true))]);
+            let result = solver.solve();
+            assert_eq!(result == SatResult::Sat, expected.is_some());
+            if result == SatResult::Sat {
+                assert!(models_satisfy(&solver.models, &clauses));
+            }
+
+            // The objective is fixed by the symmetries.
+            let mut solver = Solver::with_config(Config {
+                symmetry: true,
+                ..Config::default()
+            });
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let optimum = solver.minimize(&objective, |_| {});
+            assert_eq!(optimum, expected.map_or(Optimum::Unsat, Optimum::Found));
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::types::{bool::LitBool, lit::Lit};
+
+use super::{SatResult, Solver};
+
+/// `Rng` is the xorshift generator of random formulas in tests, whose seed is fixed
+/// so that every run checks the same formulas.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new() -> Rng {
+        Rng(88172645463325252)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a random literal of the first `num_var` variables.
+    pub fn lit(&mut self, num_var: u32) -> Lit {
+        let v = (self.next() % num_var as u64) as u32;
+        Lit::new(v, self.next().is_multiple_of(2))
+    }
+}
+
+/// Returns `num_clause` clauses of `k` random literals of the first `num_var` variables.
+/// A clause may contain a variable more than once.
+pub fn random_kcnf(rng: &mut Rng, num_var: u32, num_clause: usize, k: usize) -> Vec<Vec<Lit>> {
+    (0..num_clause)
+        .map(|_| (0..k).map(|_| rng.lit(num_var)).collect())
+        .collect()
+}
+
+/// Returns true if `clause` is satisfied by the assignment whose `i`th bit is the value of `xi`.
+pub fn bits_satisfy(bits: u64, clause: &[Lit]) -> bool {
+    clause
+        .iter()
+        .any(|lit| (bits >> lit.var().0 & 1 == 1) == lit.pos())
+}
+
+/// Returns the sum of the coefficients of the true literals of `objective` in `bits`.
+pub fn bits_cost(bits: u64, objective: &[(i64, Lit)]) -> i64 {
+    objective
+        .iter()
+        .filter(|&&(_, lit)| bits_satisfy(bits, &[lit]))
+        .map(|&(a, _)| a)
+        .sum()
+}
+
+/// Returns all models of `clauses` over the first `num_var` variables by brute force.
+pub fn brute_force(num_var: u32, clauses: &[Vec<Lit>]) -> impl Iterator<Item = u64> + '_ {
+    (0..1u64 << num_var)
+        .filter(move |&bits| clauses.iter().all(|clause| bits_satisfy(bits, clause)))
+}
+
+/// Returns true if `models` satisfies all `clauses`.
+pub fn models_satisfy(models: &[LitBool], clauses: &[Vec<Lit>]) -> bool {
+    clauses.iter().all(|clause| {
+        clause
+            .iter()
+            .any(|lit| models.get(lit.var().0 as usize) == Some(&lit.true_lbool()))
+    })
+}
+
+/// Solve `solver` and `expected` given `clauses`, which `solver` already has.
+/// Asserts that the results are the same and the model of `solver` satisfies `clauses`.
+pub fn solve_and_compare(
+    solver: &mut Solver,
+    mut expected: Solver,
+    clauses: &[Vec<Lit>],
+) -> SatResult {
+    let result = solver.solve();
+    if result == SatResult::Sat {
+        assert!(models_satisfy(&solver.models, clauses));
+    }
+    clauses
+        .iter()
+        .for_each(|clause| expected.add_clause(clause));
+    assert_eq!(result, expected.solve());
+    result
+}
@@ -0,0 +1,360 @@
+use std::collections::HashSet;
+
+use crate::{
+    clause::alloc::CRef,
+    types::{bool::LitBool, lit::Lit},
+};
+
+use super::{data::Reason, reduce::ReduceSchedule, Solver};
+
+/// `Unhide` keeps the schedule of unhiding and the generator that randomizes its DFS.
+pub struct Unhide {
+    schedule: ReduceSchedule,
+    /// the state of a xorshift generator
+    rng: u64,
+}
+
+impl Default for Unhide {
+    fn default() -> Self {
+        Unhide::new(ReduceSchedule::new(2500, 2500))
+    }
+}
+
+impl Unhide {
+    pub fn new(schedule: ReduceSchedule) -> Unhide {
+        Unhide {
+            schedule,
+            rng: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            self.rng ^= self.rng << 13;
+            self.rng ^= self.rng >> 7;
+            self.rng ^= self.rng << 17;
+            items.swap(i, (self.rng % (i as u64 + 1)) as usize);
+        }
+    }
+}
+
+/// Time stamps of literals in a DFS of the binary implication graph.
+/// A literal implies its descendants in the DFS, whose intervals are nested in its interval.
+struct Stamps {
+    /// discovered times
+    dsc: Vec<u64>,
+    /// finished times
+    fin: Vec<u64>,
+    /// the last times that literals are observed by an edge
+    obs: Vec<u64>,
+    parent: Vec<Lit>,
+    root: Vec<Lit>,
+}
+
+impl Stamps {
+    /// Returns true if `b` is a descendant of `a`, which means that `a` implies `b`.
+    fn implies(&self, a: Lit, b: Lit) -> bool {
+        let (a, b) = (a.val() as usize, b.val() as usize);
+        self.dsc[a] < self.dsc[b] && self.fin[b] < self.fin[a]
+    }
+}
+
+/// Stamp literals by a DFS of `succ` from the literals without predecessors in a random order.
+/// A literal that implies both `v` and `!v` is failed and its negation is returned.
+fn stamp(succ: &mut [Vec<Lit>], unhide: &mut Unhide) -> (Stamps, Vec<Lit>) {
+    let num_lit = succ.len();
+    let mut stamps = Stamps {
+        dsc: vec![0; num_lit],
+        fin: vec![0; num_lit],
+        obs: vec![0; num_lit],
+        parent: (0..num_lit).map(Lit::from_idx).collect(),
+        root: (0..num_lit).map(Lit::from_idx).collect(),
+    };
+    for lits in succ.iter_mut() {
+        unhide.shuffle(lits);
+    }
+    let mut roots: Vec<Lit> = (0..num_lit)
+        .map(Lit::from_idx)
+        .filter(|&lit| {
+            succ[(!lit).val() as usize].is_empty() && !succ[lit.val() as usize].is_empty()
+        })
+        .collect();
+    unhide.shuffle(&mut roots);
+    // Literals in cycles aren't reachable from the roots.
+    let mut rest: Vec<Lit> = (0..num_lit).map(Lit::from_idx).collect();
+    unhide.shuffle(&mut rest);
+    roots.extend(rest);
+
+    let mut failed = Vec::new();
+    let mut time = 0;
+    for root in roots {
+        if stamps.dsc[root.val() as usize] != 0 {
+            continue;
+        }
+        time += 1;
+        stamps.dsc[root.val() as usize] = time;
+        stamps.obs[root.val() as usize] = time;
+        // literals on the path from `root` with the next edges to visit
+        let mut path: Vec<(Lit, usize)> = vec![(root, 0)];
+        while let Some(&mut (u, ref mut edge)) = path.last_mut() {
+            let ui = u.val() as usize;
+            if let Some(&v) = succ[ui].get(*edge) {
+                *edge += 1;
+                let (vi, not_v) = (v.val() as usize, (!v).val() as usize);
+                // `!v` is observed after an ancestor `w` of `u` is discovered, so `w` implies `v` and `!v`.
+                if stamps.dsc[stamps.root[ui].val() as usize] <= stamps.obs[not_v] {
+                    let mut w = u;
+                    while stamps.dsc[w.val() as usize] > stamps.obs[not_v] {
+                        w = stamps.parent[w.val() as usize];
+                    }
+                    failed.push(!w);
+                }
+                if stamps.dsc[vi] == 0 {
+                    stamps.parent[vi] = u;
+                    stamps.root[vi] = stamps.root[ui];
+                    time += 1;
+                    stamps.dsc[vi] = time;
+                    path.push((v, 0));
+                }
+                stamps.obs[vi] = time;
+                continue;
+            }
+            path.pop();
+            time += 1;
+            stamps.fin[ui] = time;
+            stamps.obs[ui] = time;
+        }
+    }
+    (stamps, failed)
+}
+
+impl Solver {
+    /// Unhide the binary implication graph if it is scheduled after the current number of conflicts.
+    /// It backtracks to level 0 and returns false if the formula is found unsatisfiable.
+    pub(super) fn unhide_if_ready(&mut self) -> bool {
+        let mut unhide = match self.unhide.take() {
+            Some(unhide) if unhide.schedule.ready(self.stats.conflicts) => unhide,
+            unhide => {
+                self.unhide = unhide;
+                return true;
+            }
+        };
+        unhide.schedule.advance(self.stats.conflicts);
+        let ok = self.unhide(&mut unhide);
+        self.unhide = Some(unhide);
+        ok
+    }
+
+    /// Stamp the graph of irredundant binary clauses and use the stamps for failed literal detection,
+    /// transitive reduction of binary clauses, hidden tautology elimination and
+    /// hidden literal elimination. Only edges of the DFS tree justify the simplifications,
+    /// so they are never removed by transitive reduction.
+    fn unhide(&mut self, unhide: &mut Unhide) -> bool {
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        self.stats.unhides += 1;
+        let num_lit = 2 * self.vardata.num_var();
+        let mut succ: Vec<Vec<Lit>> = vec![Vec::new(); num_lit];
+        for p in (0..num_lit).map(Lit::from_idx) {
+            if self.vardata.define(p.var()) {
+                continue;
+            }
+            for bw in self.watches.lookup_binary(p).iter() {
+                if !bw.learnt && !self.vardata.define(bw.other.var()) {
+                    succ[p.val() as usize].push(bw.other);
+                }
+            }
+        }
+        let (stamps, mut units) = stamp(&mut succ, unhide);
+        units.sort();
+        units.dedup();
+        self.stats.failed_literals += units.len() as u64;
+
+        // transitive reduction of `!u v v` by another successor `w` of `u` that implies `v`
+        let mut transitive = HashSet::new();
+        for u in (0..num_lit).map(Lit::from_idx) {
+            let mut i = 0;
+            while i < succ[u.val() as usize].len() {
+                let v = succ[u.val() as usize][i];
+                i += 1;
+                let tree = stamps.parent[v.val() as usize] == u
+                    || stamps.parent[(!u).val() as usize] == !v;
+                if !u > v || tree {
+                    continue;
+                }
+                let implied = succ[u.val() as usize]
+                    .iter()
+                    .any(|&w| w != v && stamps.implies(w, v));
+                if implied {
+                    succ[u.val() as usize].retain(|&w| w != v);
+                    succ[(!v).val() as usize].retain(|&w| w != !u);
+                    transitive.insert((!u, v));
+                    self.stats.transitive_binaries += 1;
+                    i = 0;
+                }
+            }
+        }
+        if !transitive.is_empty() {
+            self.watches.retain_binary(|p, bw| {
+                let clause = if !p < bw.other {
+                    (!p, bw.other)
+                } else {
+                    (bw.other, !p)
+                };
+                bw.learnt || !transitive.contains(&clause)
+            });
+        }
+
+        let mut crefs: Vec<CRef> = self.db.clauses().to_vec();
+        crefs.extend_from_slice(self.db.learnts());
+        for cref in crefs {
+            let clause = self.db.get_mut(cref);
+            if clause.deleted() {
+                continue;
+            }
+            let learnt = clause.learnt();
+            let lits: Vec<Lit> = clause.iter().copied().collect();
+            if lits
+                .iter()
+                .any(|&lit| self.vardata.eval(lit) == LitBool::True)
+            {
+                continue;
+            }
+            let mut kept: Vec<Lit> = lits
+                .iter()
+                .copied()
+                .filter(|&lit| self.vardata.eval(lit) != LitBool::False)
+                .collect();
+            // `!a` implies `b`, so `a v b` subsumes the clause.
+            let tautology = kept
+                .iter()
+                .any(|&a| kept.iter().any(|&b| a != b && stamps.implies(!a, b)));
+            let (c0, c1) = (lits[0], lits[1]);
+            if tautology {
+                self.watches.unwatch(c0, cref);
+                self.watches.unwatch(c1, cref);
+                self.db.free(cref);
+                self.stats.hidden_tautologies += 1;
+                continue;
+            }
+            // `a` implies `b`, so `a` is removed by the resolvent with `!a v b`.
+            let mut i = 0;
+            while i < kept.len() {
+                let a = kept[i];
+                if kept.iter().any(|&b| a != b && stamps.implies(a, b)) {
+                    kept.remove(i);
+                    self.stats.hidden_literals += 1;
+                } else {
+                    i += 1;
+                }
+            }
+            if kept.len() == lits.len() {
+                continue;
+            }
+            self.watches.unwatch(c0, cref);
+            self.watches.unwatch(c1, cref);
+            match kept.len() {
+                0 => return false,
+                1 => {
+                    self.db.free(cref);
+                    units.push(kept[0]);
+                }
+                2 => {
+                    self.db.free(cref);
+                    self.watches.watch_binary(kept[0], kept[1], learnt);
+                }
+                _ => {
+                    let mut clause = self.db.get_mut(cref);
+                    for (i, &lit) in kept.iter().enumerate() {
+                        clause[i] = lit;
+                    }
+                    self.db.shrink(cref, kept.len());
+                    self.watches.watch(&kept, cref);
+                }
+            }
+        }
+        self.db.retain_clauses();
+        self.db.retain_learnts();
+
+        for lit in units {
+            match self.vardata.eval(lit) {
+                LitBool::True => {}
+                LitBool::False => return false,
+                _ => self.vardata.enqueue(lit, Reason::Undef),
+            }
+        }
+        self.propagate().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            reduce::ReduceSchedule,
+            testing::{random_kcnf, solve_and_compare, Rng},
+            SatResult, Solver,
+        },
+        types::{bool::LitBool, lit::Lit},
+    };
+
+    use super::Unhide;
+
+    #[test]
+    fn test_unhide() {
+        let mut solver = Solver::new();
+        let lits: Vec<Lit> = (0..8).map(|i| Lit::new(i, true)).collect();
+        let (a, b, c, d, e, f) = (lits[0], lits[1], lits[2], lits[3], lits[4], lits[5]);
+        // a -> b -> c and a -> c
+        solver.add_clause(&[!a, b]);
+        solver.add_clause(&[!b, c]);
+        solver.add_clause(&[!a, c]);
+        solver.add_clause(&[!c, d, e]);
+        solver.add_clause(&[!a, !b, d, e]);
+        solver.add_clause(&[!a, c, e, f]);
+        // x -> y and x -> !y
+        let (x, y) = (lits[6], lits[7]);
+        solver.add_clause(&[!x, y]);
+        solver.add_clause(&[!x, !y]);
+
+        // Only some of the implications are found by a random DFS.
+        let mut unhide = Unhide::new(ReduceSchedule::new(0, 0));
+        for _ in 0..10 {
+            assert!(solver.unhide(&mut unhide));
+        }
+        assert_eq!(solver.vardata.eval(x), LitBool::False);
+        // `!a v c` is implied by `!a v b` and `!b v c`.
+        assert_eq!(solver.stats.transitive_binaries, 1);
+        assert!(!solver
+            .watches
+            .lookup_binary(a)
+            .iter()
+            .any(|bw| bw.other == c));
+        // `!b` implies `!a`, so `!a v !b v d v e` is shortened to `!a v d v e`.
+        assert_eq!(solver.stats.hidden_literals, 1);
+        // `a` implies `c`, so `!a v c v e v f` is a hidden tautology.
+        assert_eq!(solver.stats.hidden_tautologies, 1);
+        assert_eq!(solver.solve(), SatResult::Sat);
+    }
+
+    #[test]
+    fn test_solve_with_unhiding() {
+        let mut rng = Rng::new();
+        let mut unhidden = 0;
+        for _ in 0..30 {
+            // binary clauses make the implication graph.
+            let mut clauses = random_kcnf(&mut rng, 100, 30, 2);
+            clauses.extend(random_kcnf(&mut rng, 100, 380, 3));
+            let mut solver = Solver::new();
+            solver.unhide = Some(Unhide::new(ReduceSchedule::new(10, 0)));
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let mut expected = Solver::new();
+            expected.unhide = None;
+            solve_and_compare(&mut solver, expected, &clauses);
+            unhidden += solver.stats.hidden_literals + solver.stats.hidden_tautologies;
+        }
+        assert!(unhidden > 0);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    clause::alloc::CRef,
+    types::{bool::LitBool, lit::Lit},
+};
+
+use super::{
+    data::Reason,
+    reduce::{ReduceSchedule, TIER2_LBD},
+    Solver,
+};
+
+/// Vivification can propagate this fraction of the propagations of search since the last one.
+const VIVIFY_EFFORT: f64 = 0.1;
+/// Vivification propagates at least this many literals.
+const VIVIFY_MIN_EFFORT: u64 = 10_000;
+
+/// `Vivify` keeps the schedule and the progress of vivification between restarts.
+pub struct Vivify {
+    schedule: ReduceSchedule,
+    /// `stats.propagations` at the end of the last vivification
+    propagations: u64,
+    /// The index of the next candidate in irredundant clauses
+    next_clause: usize,
+    /// The index of the next candidate in learnt clauses
+    next_learnt: usize,
+}
+
+impl Default for Vivify {
+    fn default() -> Self {
+        Vivify::new(ReduceSchedule::new(1000, 1000))
+    }
+}
+
+impl Vivify {
+    pub fn new(schedule: ReduceSchedule) -> Vivify {
+        Vivify {
+            schedule,
+            propagations: 0,
+            next_clause: 0,
+            next_learnt: 0,
+        }
+    }
+}
+
+impl Solver {
+    /// Vivify clauses if it is scheduled after the current number of conflicts.
+    /// It backtracks to level 0 and returns false if the formula is found unsatisfiable.
+    pub(super) fn vivify_if_ready(&mut self) -> bool {
+        let mut vivify = match self.vivify.take() {
+            Some(vivify) if vivify.schedule.ready(self.stats.conflicts) => vivify,
+            vivify => {
+                self.vivify = vivify;
+                return true;
+            }
+        };
+        vivify.schedule.advance(self.stats.conflicts);
+        let ok = self.vivify(&mut vivify);
+        vivify.propagations = self.stats.propagations;
+        self.vivify = Some(vivify);
+        ok
+    }
+
+    /// Shorten tier2 learnt clauses and then irredundant clauses until the effort runs out.
+    fn vivify(&mut self, vivify: &mut Vivify) -> bool {
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        self.stats.vivifications += 1;
+        let effort = (self.stats.propagations - vivify.propagations) as f64 * VIVIFY_EFFORT;
+        let effort = (effort as u64).max(VIVIFY_MIN_EFFORT);
+        // Decisions of vivification must not overwrite the phases of search.
+        let saved = self.vardata.phases.saved.clone();
+
+        let limit = self.stats.propagations + effort / 2;
+        let mut ok = true;
+        let candidates: Vec<CRef> = self.db.learnts().to_vec();
+        vivify.next_learnt = vivify.next_learnt.min(candidates.len());
+        while ok && vivify.next_learnt < candidates.len() && self.stats.propagations < limit {
+            let cref = candidates[vivify.next_learnt];
+            vivify.next_learnt += 1;
+            if self.db.get_mut(cref).lbd() <= TIER2_LBD {
+                ok = self.vivify_clause(cref);
+            }
+        }
+        if vivify.next_learnt >= candidates.len() {
+            vivify.next_learnt = 0;
+        }
+
+        let limit = self.stats.propagations + effort / 2;
+        let candidates: Vec<CRef> = self.db.clauses().to_vec();
+        vivify.next_clause = vivify.next_clause.min(candidates.len());
+        while ok && vivify.next_clause < candidates.len() && self.stats.propagations < limit {
+            let cref = candidates[vivify.next_clause];
+            vivify.next_clause += 1;
+            ok = self.vivify_clause(cref);
+        }
+        if vivify.next_clause >= candidates.len() {
+            vivify.next_clause = 0;
+        }
+
+        self.vardata.phases.saved = saved;
+        self.db.retain_clauses();
+        self.db.retain_learnts();
+        ok
+    }
+
+    /// Assign the negation of the literals of a clause one by one at level 0 and shorten the clause
+    /// by the literals before a conflict or a literal that is implied true.
+    /// Literals that are implied false are removed.
+    /// Returns false if the formula is found unsatisfiable.
+    fn vivify_clause(&mut self, cref: CRef) -> bool {
+        debug_assert!(self.vardata.trail.decision_level() == 0);
+        let clause = self.db.get_mut(cref);
+        if clause.deleted() {
+            return true;
+        }
+        let learnt = clause.learnt();
+        let lits: Vec<Lit> = clause.iter().copied().collect();
+        if lits
+            .iter()
+            .any(|&lit| self.vardata.eval(lit) == LitBool::True)
+        {
+            return true;
+        }
+        // The clause must not propagate itself.
+        self.watches.unwatch(lits[0], cref);
+        self.watches.unwatch(lits[1], cref);
+
+        let mut kept = Vec::with_capacity(lits.len());
+        for &lit in lits.iter() {
+            match self.vardata.eval(lit) {
+                LitBool::True => {
+                    kept.push(lit);
+                    break;
+                }
+                LitBool::False => {}
+                _ => {
+                    kept.push(lit);
+                    self.vardata.trail.new_decision_level();
+                    self.vardata.enqueue(!lit, Reason::Undef);
+                    if self.propagate().is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+        self.vardata.cancel_trail_until(0);
+
+        if kept.len() == lits.len() {
+            self.watches.watch(&lits, cref);
+            return true;
+        }
+        self.stats.vivified_clauses += 1;
+        self.stats.vivified_lits += (lits.len() - kept.len()) as u64;
+        match kept.len() {
+            0 => unreachable!("the clause is falsified at level 0"),
+            1 => {
+                self.db.free(cref);
+                self.vardata.enqueue(kept[0], Reason::Undef);
+                self.propagate().is_none()
+            }
+            2 => {
+                self.db.free(cref);
+                self.watches.watch_binary(kept[0], kept[1], learnt);
+                true
+            }
+            _ => {
+                let mut clause = self.db.get_mut(cref);
+                for (i, &lit) in kept.iter().enumerate() {
+                    clause[i] = lit;
+                }
+                self.db.shrink(cref, kept.len());
+                self.watches.watch(&kept, cref);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            reduce::ReduceSchedule,
+            testing::{random_kcnf, solve_and_compare, Rng},
+            SatResult, Solver,
+        },
+        types::lit::Lit,
+    };
+
+    use super::Vivify;
+
+    #[test]
+    fn test_vivify_clause() {
+        let mut solver = Solver::new();
+        let (a, b, c, d, e) = (
+            Lit::new(0, true),
+            Lit::new(1, true),
+            Lit::new(2, true),
+            Lit::new(3, true),
+            Lit::new(4, true),
+        );
+        solver.add_clause(&[a, b]);
+        solver.add_clause(&[!b, c, e]);
+        solver.add_clause(&[!b, c, !e]);
+        solver.add_clause(&[a, c, d, e]);
+        let cref = solver.db.clauses()[2];
+        // `!a` implies `b` and then `!c` is a conflict, so `a v c` subsumes `a v c v d v e`.
+        assert!(solver.vivify_clause(cref));
+        assert!(solver.db.get_mut(cref).deleted());
+        assert_eq!(solver.stats.vivified_lits, 2);
+        assert!(solver
+            .watches
+            .lookup_binary(!a)
+            .iter()
+            .any(|bw| bw.other == c));
+
+        // `!a` implies `!x`, so `x` is removed and the clause is shortened in place.
+        let (x, y, z) = (Lit::new(5, true), Lit::new(6, true), Lit::new(7, true));
+        solver.add_clause(&[a, !x]);
+        solver.add_clause(&[a, x, y, z]);
+        let cref = solver.db.clauses()[3];
+        assert!(solver.vivify_clause(cref));
+        let clause = solver.db.get_mut(cref);
+        assert!(!clause.deleted());
+        assert_eq!(clause.iter().copied().collect::<Vec<_>>(), vec![a, y, z]);
+        assert_eq!(solver.stats.vivified_lits, 3);
+        assert_eq!(solver.solve(), SatResult::Sat);
+    }
+
+    #[test]
+    fn test_solve_with_vivification() {
+        let mut rng = Rng::new();
+        let mut vivified = 0;
+        for _ in 0..10 {
+            let clauses = random_kcnf(&mut rng, 100, 426, 3);
+            let mut solver = Solver::new();
+            solver.vivify = Some(Vivify::new(ReduceSchedule::new(10, 0)));
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let mut expected = Solver::new();
+            expected.vivify = None;
+            solve_and_compare(&mut solver, expected, &clauses);
+            vivified += solver.stats.vivified_clauses;
+        }
+        assert!(vivified > 0);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{collections::idxvec::VarVec, types::var::Var};
+
+use super::branching::Branching;
+
+/// A link of a variable in the doubly linked queue.
+#[derive(Clone, Copy, Default)]
+struct Link {
+    prev: Var,
+    next: Var,
+}
+
+/// `Vmtf` (variable move to front) keeps variables in a doubly linked queue ordered by timestamps.
+/// Variables in a conflict are moved to the front (`last`) of the queue
+/// and a decision picks the unassigned variable closest to the front.
+#[derive(Default)]
+pub struct Vmtf {
+    links: VarVec<Link>,
+    /// The timestamp when a variable was moved to the front
+    stamp: VarVec<u64>,
+    assigned: VarVec<bool>,
+    /// The most recently bumped variable
+    last: Var,
+    /// The cached search position. All variables after it are assigned.
+    search: Var,
+    /// The current timestamp
+    timestamp: u64,
+    /// Variables bumped in the current conflict
+    bumped: Vec<Var>,
+}
+
+impl Vmtf {
+    fn dequeue(&mut self, v: Var) {
+        let Link { prev, next } = self.links[v];
+        if prev != Var::UNDEF {
+            self.links[prev].next = next;
+        }
+        if next == Var::UNDEF {
+            self.last = prev;
+        } else {
+            self.links[next].prev = prev;
+        }
+    }
+
+    /// Move `v` to the front of the queue.
+    fn enqueue(&mut self, v: Var) {
+        self.links[v] = Link {
+            prev: self.last,
+            next: Var::UNDEF,
+        };
+        if self.last != Var::UNDEF {
+            self.links[self.last].next = v;
+        }
+        self.last = v;
+        self.timestamp += 1;
+        self.stamp[v] = self.timestamp;
+        if !self.assigned[v] {
+            self.search = v;
+        }
+    }
+}
+
+impl Branching for Vmtf {
+    fn new_var(&mut self, v: Var) {
+        self.links.push(Link::default());
+        self.stamp.push(0);
+        self.assigned.push(false);
+        self.enqueue(v);
+    }
+    fn on_assign(&mut self, v: Var) {
+        self.assigned[v] = true;
+    }
+    fn on_unassign(&mut self, v: Var) {
+        self.assigned[v] = false;
+        if self.search == Var::UNDEF || self.stamp[self.search] < self.stamp[v] {
+            self.search = v;
+        }
+    }
+    fn bump(&mut self, v: Var) {
+        self.bumped.push(v);
+    }
+    fn decay(&mut self) {
+        // Keep the relative order of bumped variables.
+        let mut bumped = std::mem::take(&mut self.bumped);
+        bumped.sort_unstable_by_key(|&v| self.stamp[v]);
+        for &v in bumped.iter() {
+            if v != self.last {
+                self.dequeue(v);
+                self.enqueue(v);
+            } else if !self.assigned[v] {
+                self.search = v;
+            }
+        }
+        bumped.clear();
+        self.bumped = bumped;
+    }
+    fn pick(&mut self) -> Option<Var> {
+        while self.search != Var::UNDEF {
+            let v = self.search;
+            if !self.assigned[v] {
+                return Some(v);
+            }
+            self.search = self.links[v].prev;
+        }
+        None
+    }
+    fn peek(&mut self, _assigned: &dyn Fn(Var) -> bool) -> Option<Var> {
+        // `pick` doesn't move the search position beyond an unassigned variable.
+        self.pick()
+    }
+    fn prefer(&self, a: Var, b: Var) -> bool {
+        self.stamp[a] > self.stamp[b]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vmtf() {
+        let mut vmtf = Vmtf::default();
+        for i in 0..4 {
+            vmtf.new_var(Var(i));
+        }
+        assert_eq!(vmtf.pick(), Some(Var(3)));
+        vmtf.on_assign(Var(3));
+        vmtf.on_assign(Var(2));
+        assert_eq!(vmtf.pick(), Some(Var(1)));
+        vmtf.on_assign(Var(1));
+
+        // Var(2) stays in front of Var(0) because it was moved more recently.
+        vmtf.bump(Var(0));
+        vmtf.bump(Var(2));
+        vmtf.on_unassign(Var(1));
+        vmtf.decay();
+        assert_eq!(vmtf.pick(), Some(Var(0)));
+        vmtf.on_assign(Var(0));
+        assert_eq!(vmtf.pick(), Some(Var(1)));
+
+        vmtf.on_unassign(Var(2));
+        assert_eq!(vmtf.pick(), Some(Var(2)));
+    }
+}
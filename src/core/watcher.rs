@@ -1,4 +1,8 @@
-use crate::{clause::alloc::CRef, collections::idxvec::LitVec, types::lit::Lit};
+use crate::{
+    clause::{alloc::CRef, db::ClauseDB},
+    collections::idxvec::LitVec,
+    types::lit::Lit,
+};
 
 /// `blocker` is clause[0] or clause[1].
 /// `cref` points that a clause that is watched.
@@ -13,20 +17,33 @@ impl Watch {
     }
 }
 
+/// `BinWatch` watches a binary clause that is not stored in `ClauseDB`.
+/// `other` is the other literal of a binary clause.
+#[derive(Debug, Clone, Copy)]
+pub struct BinWatch {
+    pub other: Lit,
+    pub learnt: bool,
+}
+
 pub struct Watchers {
     watchers: LitVec<Vec<Watch>>,
+    /// watches for binary clauses
+    binary: LitVec<Vec<BinWatch>>,
 }
 
 impl Watchers {
     pub fn new() -> Watchers {
         Watchers {
             watchers: LitVec::new(),
+            binary: LitVec::new(),
         }
     }
 
     pub fn new_var(&mut self) {
         self.watchers.push(Vec::new());
         self.watchers.push(Vec::new());
+        self.binary.push(Vec::new());
+        self.binary.push(Vec::new());
     }
 
     pub fn as_mut_ptr(&mut self) -> *mut LitVec<Vec<Watch>> {
@@ -40,7 +57,51 @@ impl Watchers {
         self.watchers[!c1].push(Watch::new(cref, c0));
     }
 
+    /// Stop watching `lit` of a clause `cref`.
+    pub fn unwatch(&mut self, lit: Lit, cref: CRef) {
+        let ws = &mut self.watchers[!lit];
+        if let Some(idx) = ws.iter().position(|w| w.cref == cref) {
+            ws.swap_remove(idx);
+        }
+    }
+
     pub fn lookup_mut(&mut self, lit: Lit) -> &mut Vec<Watch> {
         &mut self.watchers[lit]
     }
+
+    /// Watch a binary clause `c0 v c1`.
+    pub fn watch_binary(&mut self, c0: Lit, c1: Lit, learnt: bool) {
+        self.binary[!c0].push(BinWatch { other: c1, learnt });
+        self.binary[!c1].push(BinWatch { other: c0, learnt });
+    }
+
+    /// Returns binary clauses that become unit or conflict when `lit` is true.
+    pub fn lookup_binary(&self, lit: Lit) -> &Vec<BinWatch> {
+        &self.binary[lit]
+    }
+
+    /// Keep binary clauses `!p v bw.other` for which `f(p, bw)` returns true.
+    /// `f` must return the same result for both watches of a clause.
+    pub fn retain_binary(&mut self, mut f: impl FnMut(Lit, &BinWatch) -> bool) {
+        for (idx, bws) in self.binary.iter_mut().enumerate() {
+            let p = Lit::from_idx(idx);
+            bws.retain(|bw| f(p, bw));
+        }
+    }
+
+    /// Rewrite the `cref` of all watches by `reloc`.
+    pub fn relocate(&mut self, mut reloc: impl FnMut(CRef) -> CRef) {
+        for ws in self.watchers.iter_mut() {
+            for w in ws.iter_mut() {
+                w.cref = reloc(w.cref);
+            }
+        }
+    }
+
+    /// Remove all watches that point deleted clauses.
+    pub fn remove_deleted(&mut self, db: &mut ClauseDB) {
+        for ws in self.watchers.iter_mut() {
+            ws.retain(|w| !db.get_mut(w.cref).deleted());
+        }
+    }
 }
@@ -0,0 +1,602 @@
+use std::collections::HashSet;
+
+use crate::types::{bool::LitBool, lit::Lit, var::Var};
+
+use super::{data::VarData, elim::Occurs, Conflict, SatResult, Solver};
+
+/// XOR constraints of at most this many variables are recovered from clauses.
+const XOR_DETECT_MAX_SIZE: usize = 5;
+/// The recovery of XOR constraints visits at most this many clauses.
+const XOR_DETECT_EFFORT: u64 = 10_000_000;
+
+/// `Xor` is a constraint that the sum of `vars` modulo 2 is `rhs`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Xor {
+    vars: Vec<Var>,
+    rhs: bool,
+}
+
+impl Xor {
+    /// Returns the constraint that an odd number of `lits` are true.
+    /// Two literals of the same variable cancel each other out.
+    fn new(lits: &[Lit]) -> Xor {
+        let mut vars: Vec<Var> = lits.iter().map(|lit| lit.var()).collect();
+        vars.sort_unstable();
+        let mut kept: Vec<Var> = Vec::with_capacity(vars.len());
+        for v in vars {
+            if kept.last() == Some(&v) {
+                kept.pop();
+            } else {
+                kept.push(v);
+            }
+        }
+        Xor {
+            vars: kept,
+            rhs: lits.iter().filter(|lit| lit.neg()).count() % 2 == 0,
+        }
+    }
+}
+
+/// Returns the columns of the set bits of a row.
+fn columns(row: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    row.iter().enumerate().flat_map(|(i, &word)| {
+        let mut word = word;
+        std::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(64 * i + bit)
+        })
+    })
+}
+
+/// `RowState` is what a row of a matrix implies under the current assignment.
+#[derive(Debug, PartialEq)]
+enum RowState {
+    /// The row is satisfied or has two or more unassigned variables.
+    Open,
+    /// The row implies its only unassigned literal.
+    Unit(Lit),
+    /// All variables of the row are assigned and their sum is wrong.
+    Conflict,
+}
+
+/// `Matrix` propagates XOR constraints over connected variables by Gauss-Jordan elimination.
+/// Each row has a pivot column that no other row has, and the pivot is moved to another
+/// unassigned column of the row when it is assigned. Then a row that has one unassigned variable
+/// implies it, and no other literals are implied by the constraints.
+struct Matrix {
+    /// the variable of each column
+    vars: Vec<Var>,
+    /// bit sets of the columns of each row
+    rows: Vec<Vec<u64>>,
+    /// the right-hand side of each row
+    rhs: Vec<bool>,
+    /// the pivot column of each row
+    pivots: Vec<usize>,
+    /// True if a variable of the matrix is assigned since the last check.
+    dirty: bool,
+}
+
+impl Matrix {
+    /// Returns the reduced row echelon form of `xors` over `vars`,
+    /// or `None` if they are inconsistent.
+    fn new(vars: Vec<Var>, xors: &[Xor]) -> Option<Matrix> {
+        let words = vars.len().div_ceil(64);
+        let mut rows = Vec::with_capacity(xors.len());
+        let mut rhs = Vec::with_capacity(xors.len());
+        for xor in xors.iter() {
+            let mut row = vec![0; words];
+            for v in xor.vars.iter() {
+                let col = vars.binary_search(v).expect("no column");
+                row[col / 64] ^= 1 << (col % 64);
+            }
+            rows.push(row);
+            rhs.push(xor.rhs);
+        }
+        let mut matrix = Matrix {
+            vars,
+            rows,
+            rhs,
+            pivots: Vec::new(),
+            dirty: true,
+        };
+        let mut r = 0;
+        while r < matrix.rows.len() {
+            let first = columns(&matrix.rows[r]).next();
+            match first {
+                Some(col) => {
+                    matrix.pivots.push(col);
+                    matrix.eliminate(r, col);
+                    r += 1;
+                }
+                // `0 = 1`
+                None if matrix.rhs[r] => return None,
+                // a linear combination of the other rows
+                None => {
+                    matrix.rows.swap_remove(r);
+                    matrix.rhs.swap_remove(r);
+                }
+            }
+        }
+        Some(matrix)
+    }
+
+    /// Remove `col` from the rows other than `r` by adding `r` to them.
+    fn eliminate(&mut self, r: usize, col: usize) {
+        let (word, bit) = (col / 64, 1 << (col % 64));
+        let row = std::mem::take(&mut self.rows[r]);
+        for s in 0..self.rows.len() {
+            if s == r || self.rows[s][word] & bit == 0 {
+                continue;
+            }
+            for (x, y) in self.rows[s].iter_mut().zip(row.iter()) {
+                *x ^= y;
+            }
+            self.rhs[s] ^= self.rhs[r];
+        }
+        self.rows[r] = row;
+    }
+
+    /// Returns the state of row `r` and whether its pivot is moved.
+    /// The pivot is moved to another unassigned column if it is assigned.
+    fn update(&mut self, r: usize, vardata: &VarData) -> (RowState, bool) {
+        let pivot = self.pivots[r];
+        // the sum of the unassigned variables
+        let mut parity = self.rhs[r];
+        let mut unassigned = 0;
+        let mut last = pivot;
+        let mut free = None;
+        for col in columns(&self.rows[r]) {
+            match vardata.lbool(self.vars[col]) {
+                LitBool::True => parity = !parity,
+                LitBool::False => {}
+                _ => {
+                    unassigned += 1;
+                    last = col;
+                    if col != pivot {
+                        free = Some(col);
+                    }
+                }
+            }
+        }
+        let mut moved = false;
+        if let Some(col) = free {
+            if vardata.define(self.vars[pivot]) {
+                self.pivots[r] = col;
+                self.eliminate(r, col);
+                moved = true;
+            }
+        }
+        let state = match unassigned {
+            0 if parity => RowState::Conflict,
+            1 => RowState::Unit(Lit::new(self.vars[last].val(), parity)),
+            _ => RowState::Open,
+        };
+        (state, moved)
+    }
+
+    /// Returns the literals of the assigned variables of row `r`, which are false.
+    fn false_lits(&self, r: usize, vardata: &VarData) -> Vec<Lit> {
+        columns(&self.rows[r])
+            .map(|col| self.vars[col])
+            .filter(|&v| vardata.define(v))
+            .map(|v| Lit::new(v.val(), vardata.lbool(v) == LitBool::False))
+            .collect()
+    }
+}
+
+/// `Gauss` keeps XOR constraints and their matrices, which are built before search.
+pub struct Gauss {
+    xors: Vec<Xor>,
+    /// `xors` without duplicates
+    known: HashSet<Xor>,
+    /// a matrix for each connected component of the variables of `xors`
+    matrices: Vec<Matrix>,
+    /// the matrix of each variable
+    matrix_of: Vec<Option<usize>>,
+    /// True if constraints are added since the matrices were built.
+    pending: bool,
+    /// True if a matrix may have to be checked.
+    dirty: bool,
+    /// Recover XOR constraints from clauses before search.
+    pub(super) detect: bool,
+}
+
+impl Gauss {
+    pub fn new(detect: bool) -> Gauss {
+        Gauss {
+            xors: Vec::new(),
+            known: HashSet::new(),
+            matrices: Vec::new(),
+            matrix_of: Vec::new(),
+            pending: false,
+            dirty: false,
+            detect,
+        }
+    }
+
+    /// Add a constraint unless it is already added. Returns true if it is new.
+    fn add(&mut self, xor: Xor) -> bool {
+        if !self.known.insert(xor.clone()) {
+            return false;
+        }
+        self.xors.push(xor);
+        self.pending = true;
+        true
+    }
+
+    /// Returns the variables of XOR constraints, which must not be eliminated.
+    pub(super) fn vars(&self) -> impl Iterator<Item = Var> + '_ {
+        self.xors.iter().flat_map(|xor| xor.vars.iter().copied())
+    }
+
+    /// Mark the matrices of assigned `lits`. Returns true if any matrix has to be checked.
+    pub(super) fn touch(&mut self, lits: &[Lit]) -> bool {
+        if self.matrices.is_empty() {
+            return false;
+        }
+        for lit in lits.iter() {
+            if let Some(&Some(m)) = self.matrix_of.get(lit.var().val() as usize) {
+                self.matrices[m].dirty = true;
+                self.dirty = true;
+            }
+        }
+        self.dirty
+    }
+
+    /// Build a matrix for each connected component of the constraints
+    /// simplified by the assignments at level 0. Returns false if they are inconsistent.
+    fn build(&mut self, vardata: &VarData) -> bool {
+        debug_assert!(vardata.trail.decision_level() == 0);
+        self.pending = false;
+        self.matrices.clear();
+        self.matrix_of = vec![None; vardata.num_var()];
+        let mut xors = Vec::new();
+        for xor in self.xors.iter() {
+            let mut rhs = xor.rhs;
+            let mut vars = Vec::with_capacity(xor.vars.len());
+            for &v in xor.vars.iter() {
+                match vardata.lbool(v) {
+                    LitBool::True => rhs = !rhs,
+                    LitBool::False => {}
+                    _ => vars.push(v),
+                }
+            }
+            if vars.is_empty() {
+                if rhs {
+                    return false;
+                }
+                continue;
+            }
+            xors.push(Xor { vars, rhs });
+        }
+
+        // Constraints that share variables are in the same component by union-find.
+        let mut parent: Vec<usize> = (0..vardata.num_var()).collect();
+        fn find(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                parent[x] = parent[parent[x]];
+                x = parent[x];
+            }
+            x
+        }
+        for xor in xors.iter() {
+            let root = find(&mut parent, xor.vars[0].val() as usize);
+            for v in xor.vars.iter().skip(1) {
+                let other = find(&mut parent, v.val() as usize);
+                parent[other] = root;
+            }
+        }
+        let mut component_of = vec![usize::MAX; vardata.num_var()];
+        let mut components: Vec<Vec<Xor>> = Vec::new();
+        for xor in xors {
+            let root = find(&mut parent, xor.vars[0].val() as usize);
+            if component_of[root] == usize::MAX {
+                component_of[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[component_of[root]].push(xor);
+        }
+        for xors in components {
+            let mut vars: Vec<Var> = xors
+                .iter()
+                .flat_map(|xor| xor.vars.iter().copied())
+                .collect();
+            vars.sort_unstable();
+            vars.dedup();
+            let matrix = match Matrix::new(vars, &xors) {
+                Some(matrix) => matrix,
+                None => return false,
+            };
+            if matrix.rows.is_empty() {
+                continue;
+            }
+            for v in matrix.vars.iter() {
+                self.matrix_of[v.val() as usize] = Some(self.matrices.len());
+            }
+            self.matrices.push(matrix);
+        }
+        self.dirty = true;
+        true
+    }
+}
+
+impl Solver {
+    /// Add an XOR constraint that an odd number of `lits` are true,
+    /// which is written as `x1 -2 3 0` in CNF files of CryptoMiniSat.
+    /// Constraints of at most two variables are added as clauses.
+    pub fn add_xor(&mut self, lits: &[Lit]) {
+        if self.result == SatResult::Unsat {
+            return;
+        }
+        self.prepare_constraint(lits);
+
+        let xor = Xor::new(lits);
+        match xor.vars.len() {
+            0 => {
+                if xor.rhs {
+                    self.result = SatResult::Unsat;
+                }
+            }
+            1 => self.add_clause(&[Lit::new(xor.vars[0].val(), xor.rhs)]),
+            2 => {
+                // `x = y` or `x = !y`
+                let x = Lit::new(xor.vars[0].val(), true);
+                let y = Lit::new(xor.vars[1].val(), !xor.rhs);
+                self.add_clause(&[!x, y]);
+                self.add_clause(&[x, !y]);
+            }
+            _ => {
+                self.gauss.add(xor);
+            }
+        }
+    }
+
+    /// Recover XOR constraints from their encodings by clauses in `occurs`.
+    /// The clauses are kept, so they still propagate as well as the constraints.
+    pub(super) fn detect_xors(&mut self, occurs: &mut Occurs) {
+        if !self.gauss.detect {
+            return;
+        }
+        let mut used = vec![false; occurs.clauses.len()];
+        let mut ticks = 0;
+        for i in 0..occurs.clauses.len() {
+            let size = occurs.clauses[i].len();
+            if ticks > XOR_DETECT_EFFORT
+                || used[i]
+                || occurs.removed[i]
+                || !(3..=XOR_DETECT_MAX_SIZE).contains(&size)
+            {
+                continue;
+            }
+            // Every clause of the constraint has the variable of any literal of the clause.
+            let mut best = occurs.clauses[i][0];
+            let mut fewest = usize::MAX;
+            for k in 0..size {
+                let lit = occurs.clauses[i][k];
+                let num_occs = occurs.occs(lit).len() + occurs.occs(!lit).len();
+                if num_occs < fewest {
+                    best = lit;
+                    fewest = num_occs;
+                }
+            }
+            if fewest < 1 << (size - 1) {
+                continue;
+            }
+            let mut candidates = occurs.occs(best).to_vec();
+            candidates.extend_from_slice(occurs.occs(!best));
+            ticks += candidates.len() as u64;
+            if let Some(clauses) = occurs.xor_clauses(i, &candidates) {
+                for &j in clauses.iter() {
+                    used[j] = true;
+                }
+                // The clause forbids the assignments of the other parity.
+                if self.gauss.add(Xor::new(&occurs.clauses[i])) {
+                    self.stats.detected_xors += 1;
+                }
+            }
+        }
+    }
+
+    /// Build the matrices of XOR constraints if constraints are added since the last build.
+    /// Returns false if the formula is found unsatisfiable.
+    pub(super) fn build_matrices(&mut self) -> bool {
+        if !self.gauss.pending {
+            return true;
+        }
+        self.vardata.cancel_trail_until(0);
+        if self.propagate().is_some() {
+            return false;
+        }
+        self.gauss.build(&self.vardata) && self.propagate().is_none()
+    }
+
+    /// Check the matrices whose variables are assigned since the last check.
+    /// Returns a conflict or whether any literal is assigned.
+    pub(super) fn propagate_xors(&mut self) -> Result<bool, Conflict> {
+        let mut propagated = false;
+        for m in 0..self.gauss.matrices.len() {
+            if !self.gauss.matrices[m].dirty {
+                continue;
+            }
+            self.gauss.matrices[m].dirty = false;
+            loop {
+                let mut moved = false;
+                let mut assigned = false;
+                for r in 0..self.gauss.matrices[m].rows.len() {
+                    let (state, pivot_moved) = self.gauss.matrices[m].update(r, &self.vardata);
+                    moved |= pivot_moved;
+                    match state {
+                        RowState::Open => {}
+                        RowState::Unit(lit) => {
+                            let reason = self.gauss.matrices[m].false_lits(r, &self.vardata);
+                            self.enqueue_xor(lit, reason);
+                            assigned = true;
+                        }
+                        RowState::Conflict => {
+                            let lits = self.gauss.matrices[m].false_lits(r, &self.vardata);
+                            return Err(self.xor_conflict(lits));
+                        }
+                    }
+                }
+                propagated |= assigned;
+                // Rows before a moved pivot may have to be checked again.
+                if assigned || !moved {
+                    break;
+                }
+            }
+        }
+        self.gauss.dirty = false;
+        Ok(propagated)
+    }
+
+    /// Assign `lit` implied by a row whose other literals are `reason`.
+    fn enqueue_xor(&mut self, lit: Lit, reason: Vec<Lit>) {
+        self.stats.xor_propagations += 1;
+        let reason = self.explanation(reason);
+        let level = reason.first().map_or(0, |q| self.vardata.level(q.var()));
+        let reason = self.explained_reason(lit, &reason);
+        self.vardata.enqueue_at(lit, level, reason);
+    }
+
+    /// Returns the conflict of a row whose literals `lits` are all false.
+    fn xor_conflict(&mut self, lits: Vec<Lit>) -> Conflict {
+        self.stats.xor_conflicts += 1;
+        self.explained_conflict(lits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{
+            config::Config,
+            data::{Reason, VarData},
+            elim::Occurs,
+            phase::PhaseConfig,
+            testing::{random_kcnf, Rng},
+            SatResult, Solver,
+        },
+        types::{lit::Lit, var::Var},
+    };
+
+    use super::{Matrix, RowState, Xor};
+
+    #[test]
+    fn test_xor_new() {
+        let (a, b, c) = (Lit::new(0, true), Lit::new(1, true), Lit::new(2, true));
+        // a ^ !b ^ c = 1 is a ^ b ^ c = 0
+        let xor = Xor::new(&[a, !b, c]);
+        assert_eq!((xor.vars, xor.rhs), (vec![Var(0), Var(1), Var(2)], false));
+        // b ^ a ^ b = 1 is a = 1
+        let xor = Xor::new(&[b, a, b]);
+        assert_eq!((xor.vars, xor.rhs), (vec![Var(0)], true));
+    }
+
+    #[test]
+    fn test_matrix() {
+        let xor = |vars: &[u32], rhs: bool| Xor {
+            vars: vars.iter().map(|&v| Var(v)).collect(),
+            rhs,
+        };
+        // a ^ b ^ c = 1, b ^ c ^ d = 0 and a ^ d = 1, which is the sum of the others
+        let xors = [
+            xor(&[0, 1, 2], true),
+            xor(&[1, 2, 3], false),
+            xor(&[0, 3], true),
+        ];
+        let vars = (0..4).map(Var).collect();
+        let mut matrix = Matrix::new(vars, &xors).expect("inconsistent");
+        assert_eq!(matrix.rows.len(), 2);
+        assert!(Matrix::new(
+            (0..4).map(Var).collect(),
+            &[xors[0].clone(), xor(&[0, 1, 2], false)]
+        )
+        .is_none());
+
+        let mut vardata = VarData::new(Config::default().branching.build(), PhaseConfig::default());
+        for _ in 0..4 {
+            vardata.new_var();
+        }
+        // `b = 1` moves the pivot of the first row from `a` to `c`.
+        vardata.trail.new_decision_level();
+        vardata.enqueue(Lit::new(1, true), Reason::Undef);
+        assert_eq!(matrix.update(0, &vardata), (RowState::Open, false));
+        assert_eq!(matrix.update(1, &vardata), (RowState::Open, true));
+        // `c = 0` implies `d = 1` by the second row and `a = 0` by the first row `a ^ b ^ c = 1`.
+        vardata.enqueue(Lit::new(2, false), Reason::Undef);
+        let units: Vec<RowState> = (0..2).map(|r| matrix.update(r, &vardata).0).collect();
+        assert!(units.contains(&RowState::Unit(Lit::new(0, false))));
+        assert!(units.contains(&RowState::Unit(Lit::new(3, true))));
+        vardata.enqueue(Lit::new(0, true), Reason::Undef);
+        vardata.enqueue(Lit::new(3, true), Reason::Undef);
+        let states: Vec<RowState> = (0..2).map(|r| matrix.update(r, &vardata).0).collect();
+        assert!(states.contains(&RowState::Conflict));
+    }
+
+    #[test]
+    fn test_detect_xors() {
+        let (a, b, c, d) = (
+            Lit::new(0, true),
+            Lit::new(1, true),
+            Lit::new(2, true),
+            Lit::new(3, true),
+        );
+        let mut solver = Solver::new();
+        let mut occurs = Occurs::new(4);
+        // a ^ b ^ c = 1 and a ^ b ^ d = 0 without `a v !b v d`
+        for clause in [
+            vec![a, b, c],
+            vec![a, !b, !c],
+            vec![!a, b, !c],
+            vec![!a, !b, c],
+            vec![!a, b, d],
+            vec![a, b, !d],
+            vec![!a, !b, !d],
+        ]
+        .iter()
+        {
+            occurs.add(clause.clone());
+        }
+        for _ in 0..4 {
+            solver.new_var();
+        }
+        solver.detect_xors(&mut occurs);
+        assert_eq!(solver.stats.detected_xors, 1);
+        assert_eq!(solver.gauss.xors, vec![Xor::new(&[a, b, c])]);
+    }
+
+    #[test]
+    fn test_solve_with_xors() {
+        let mut rng = Rng::new();
+        let mut conflicts = 0;
+        for _ in 0..20 {
+            // Random XOR constraints are solved by Gaussian elimination.
+            let num_var = 60;
+            let xors = random_kcnf(&mut rng, num_var, 55, 4);
+            let mut solver = Solver::new();
+            xors.iter().for_each(|xor| solver.add_xor(xor));
+            let result = solver.solve();
+            conflicts += solver.stats.xor_conflicts;
+            if result == SatResult::Sat {
+                for xor in xors.iter() {
+                    let odd = xor
+                        .iter()
+                        .filter(|lit| solver.models[lit.var().0 as usize] == lit.true_lbool())
+                        .count()
+                        % 2
+                        == 1;
+                    assert!(odd);
+                }
+            } else {
+                let xors: Vec<Xor> = xors.iter().map(|xor| Xor::new(xor)).collect();
+                let vars = (0..num_var).map(Var).collect();
+                assert!(Matrix::new(vars, &xors).is_none());
+            }
+        }
+        assert!(conflicts > 0);
+    }
+}
@@ -1,9 +1,163 @@
 use core::panic;
 
-use clap::{App, Arg};
-use scrapsat::{core::Solver, parser};
+use clap::{App, Arg, ArgMatches};
+use scrapsat::{
+    core::{
+        branching::BranchingStrategy, config::Config, maxsat::MaxSatStrategy, optimize::Optimum,
+        phase::PhaseConfig, restart::RestartStrategy, SatResult, Solver,
+    },
+    parser,
+    types::bool::LitBool,
+};
 use signal_hook::{consts::SIGINT, iterator::Signals};
 
+fn parse_value<T: std::str::FromStr>(matches: &ArgMatches, name: &str) -> Option<T> {
+    matches.value_of(name).map(|x| {
+        x.parse::<T>()
+            .unwrap_or_else(|_| panic!("invalid value for --{}: {}", name, x))
+    })
+}
+
+fn restart_strategy(matches: &ArgMatches) -> RestartStrategy {
+    let mut strategy = parse_value(matches, "restart").unwrap_or_default();
+    match &mut strategy {
+        RestartStrategy::Luby { first, inc } => {
+            *first = parse_value(matches, "restart-first").unwrap_or(*first);
+            *inc = parse_value(matches, "restart-factor").unwrap_or(*inc);
+        }
+        RestartStrategy::Geometric { first, factor } => {
+            *first = parse_value(matches, "restart-first").unwrap_or(*first);
+            *factor = parse_value(matches, "restart-factor").unwrap_or(*factor);
+        }
+        RestartStrategy::Glucose { k, r, .. } => {
+            *k = parse_value(matches, "restart-k").unwrap_or(*k);
+            *r = parse_value(matches, "restart-r").unwrap_or(*r);
+        }
+        RestartStrategy::Ema { margin, .. } => {
+            *margin = parse_value(matches, "restart-margin").unwrap_or(*margin);
+        }
+    }
+    strategy
+}
+
+fn phase_config(matches: &ArgMatches) -> PhaseConfig {
+    let default = PhaseConfig::default();
+    PhaseConfig {
+        target: !matches.is_present("no-target-phase"),
+        rephase_interval: parse_value(matches, "rephase-interval")
+            .unwrap_or(default.rephase_interval),
+        seed: parse_value(matches, "seed").unwrap_or(default.seed),
+    }
+}
+
+/// Interrupt `solver` when SIGINT is received.
+fn interrupt_on_sigint(solver: &Solver) {
+    let sender = solver.sender.clone();
+    let mut signals = Signals::new([SIGINT]).expect("togatoga");
+    std::thread::spawn(move || {
+        for sig in signals.forever() {
+            eprintln!("{:?}", sig);
+            sender.send(0).expect("failed to send");
+        }
+    });
+}
+
+/// Solve pseudo-Boolean constraints in an OPB file and minimize its objective if any.
+/// The result is printed in the output format of the pseudo-Boolean competition.
+fn solve_opb(mut solver: Solver, input: &str) {
+    let opb = match parser::parse_opb(
+        std::fs::File::open(input).unwrap_or_else(|_| panic!("can't open file {}", input)),
+    ) {
+        Ok(opb) => opb,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            panic!("failed to parse");
+        }
+    };
+    opb.constraints
+        .iter()
+        .for_each(|(terms, degree)| solver.add_pb(terms, *degree));
+    interrupt_on_sigint(&solver);
+
+    let (status, model) = match opb.objective.as_ref() {
+        Some(objective) => match solver.minimize(objective, |cost| println!("o {}", cost)) {
+            Optimum::Found(_) => ("OPTIMUM FOUND", true),
+            Optimum::Unsat => ("UNSATISFIABLE", false),
+            Optimum::Unknown(Some(_)) => ("SATISFIABLE", true),
+            Optimum::Unknown(None) => ("UNKNOWN", false),
+        },
+        None => match solver.solve() {
+            SatResult::Sat => ("SATISFIABLE", true),
+            SatResult::Unsat => ("UNSATISFIABLE", false),
+            SatResult::Unknown => ("UNKNOWN", false),
+        },
+    };
+    println!("{}", solver.stats);
+    println!("s {}", status);
+    if model {
+        let num_var = opb
+            .num_variable
+            .map_or(solver.num_var(), |n| n as usize)
+            .max(solver.num_var());
+        let values: Vec<String> = (0..num_var)
+            .map(|v| match solver.models.get(v) {
+                Some(LitBool::True) => format!("x{}", v + 1),
+                _ => format!("-x{}", v + 1),
+            })
+            .collect();
+        println!("v {}", values.join(" "));
+    }
+}
+
+/// Solve weighted partial MaxSAT in a WCNF file.
+/// The result is printed in the output format of the MaxSAT evaluation.
+fn solve_wcnf(mut solver: Solver, input: &str, strategy: MaxSatStrategy) {
+    let wcnf = match parser::parse_wcnf(
+        std::fs::File::open(input).unwrap_or_else(|_| panic!("can't open file {}", input)),
+    ) {
+        Ok(wcnf) => wcnf,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            panic!("failed to parse");
+        }
+    };
+    // Relaxation variables follow the variables of the header.
+    while solver.num_var() < wcnf.num_variable.unwrap_or(0) as usize {
+        solver.new_var();
+    }
+    let num_var = wcnf
+        .hards
+        .iter()
+        .chain(wcnf.softs.iter().map(|(_, clause)| clause))
+        .flat_map(|clause| clause.iter())
+        .map(|lit| lit.var().val() as usize + 1)
+        .fold(solver.num_var(), usize::max);
+    wcnf.hards
+        .iter()
+        .for_each(|clause| solver.add_clause(clause));
+    interrupt_on_sigint(&solver);
+
+    let (status, model) =
+        match solver.solve_maxsat(&wcnf.softs, strategy, |cost| println!("o {}", cost)) {
+            Optimum::Found(_) => ("OPTIMUM FOUND", true),
+            Optimum::Unsat => ("UNSATISFIABLE", false),
+            Optimum::Unknown(Some(_)) => ("SATISFIABLE", true),
+            Optimum::Unknown(None) => ("UNKNOWN", false),
+        };
+    println!("{}", solver.stats);
+    println!("s {}", status);
+    if model {
+        // The values of the variables in order as a string of 0 and 1.
+        let values: String = (0..num_var)
+            .map(|v| match solver.models.get(v) {
+                Some(LitBool::True) => '1',
+                _ => '0',
+            })
+            .collect();
+        println!("v {}", values);
+    }
+}
+
 fn main() {
     let matches = App::new("scrapsat")
         .version("0.1")
@@ -11,7 +165,7 @@ fn main() {
         .about("SAT solver")
         .arg(
             Arg::with_name("input")
-                .help("input CNF file")
+                .help("input CNF file, or OPB/WCNF file if its extension is .opb/.wcnf")
                 .index(1)
                 .required(true),
         )
@@ -23,9 +177,223 @@ fn main() {
                 .value_name("sec")
                 .help("limit on CPU time allowed in seconds"),
         )
+        .arg(
+            Arg::with_name("restart")
+                .long("restart")
+                .takes_value(true)
+                .possible_values(&["luby", "geometric", "glucose", "ema"])
+                .help("restart strategy (default luby)"),
+        )
+        .arg(
+            Arg::with_name("restart-first")
+                .long("restart-first")
+                .takes_value(true)
+                .value_name("conflicts")
+                .help("the first restart limit of luby/geometric restarts"),
+        )
+        .arg(
+            Arg::with_name("restart-factor")
+                .long("restart-factor")
+                .takes_value(true)
+                .help("the factor of restart limits of luby/geometric restarts"),
+        )
+        .arg(
+            Arg::with_name("restart-k")
+                .long("restart-k")
+                .takes_value(true)
+                .help("the factor K of recent LBDs of glucose restarts"),
+        )
+        .arg(
+            Arg::with_name("restart-r")
+                .long("restart-r")
+                .takes_value(true)
+                .help("the factor R of the trail size to block glucose restarts"),
+        )
+        .arg(
+            Arg::with_name("restart-margin")
+                .long("restart-margin")
+                .takes_value(true)
+                .help("the margin between fast and slow EMAs of ema restarts"),
+        )
+        .arg(
+            Arg::with_name("branching")
+                .long("branching")
+                .takes_value(true)
+                .possible_values(&["vsids", "lrb", "chb", "vmtf"])
+                .help("branching heuristic (default vsids)"),
+        )
+        .arg(
+            Arg::with_name("chrono")
+                .long("chrono")
+                .takes_value(true)
+                .value_name("levels")
+                .help("backtrack chronologically if a backjump skips more levels (default 100)"),
+        )
+        .arg(
+            Arg::with_name("no-chrono")
+                .long("no-chrono")
+                .conflicts_with("chrono")
+                .help("always backjump to the asserting level"),
+        )
+        .arg(
+            Arg::with_name("no-reuse-trail")
+                .long("no-reuse-trail")
+                .help("backtrack to level 0 on every restart"),
+        )
+        .arg(
+            Arg::with_name("shrink").long("shrink").help(
+                "replace literals at the same level in learnt clauses by the UIP of the level",
+            ),
+        )
+        .arg(
+            Arg::with_name("no-otfs")
+                .long("no-otfs")
+                .help("don't strengthen reason clauses by on-the-fly self-subsumption"),
+        )
+        .arg(
+            Arg::with_name("eager-subsume")
+                .long("eager-subsume")
+                .takes_value(true)
+                .value_name("clauses")
+                .help("the number of recent learnt clauses checked for subsumption (default 20)"),
+        )
+        .arg(
+            Arg::with_name("no-vivify")
+                .long("no-vivify")
+                .help("don't shorten clauses by vivification between restarts"),
+        )
+        .arg(
+            Arg::with_name("no-probe")
+                .long("no-probe")
+                .help("don't probe failed literals between restarts"),
+        )
+        .arg(
+            Arg::with_name("no-subsume")
+                .long("no-subsume")
+                .help("don't remove subsumed clauses or strengthen clauses by self-subsumption"),
+        )
+        .arg(
+            Arg::with_name("no-unhide")
+                .long("no-unhide")
+                .help("don't simplify clauses by unhiding between restarts"),
+        )
+        .arg(
+            Arg::with_name("no-scc")
+                .long("no-scc")
+                .help("don't substitute equivalent literals before search"),
+        )
+        .arg(
+            Arg::with_name("no-bce")
+                .long("no-bce")
+                .help("don't remove blocked clauses before search"),
+        )
+        .arg(
+            Arg::with_name("cce")
+                .long("cce")
+                .help("remove covered clauses as well as blocked clauses before search"),
+        )
+        .arg(
+            Arg::with_name("no-elim")
+                .long("no-elim")
+                .help("don't eliminate variables before search"),
+        )
+        .arg(
+            Arg::with_name("no-xor")
+                .long("no-xor")
+                .help("don't recover XOR constraints from clauses before search"),
+        )
+        .arg(
+            Arg::with_name("no-card")
+                .long("no-card")
+                .help("don't replace at-most-k constraints in clauses by native constraints"),
+        )
+        .arg(
+            Arg::with_name("maxsat")
+                .long("maxsat")
+                .takes_value(true)
+                .possible_values(&["oll", "linear"])
+                .help("MaxSAT search of WCNF files (default oll)"),
+        )
+        .arg(
+            Arg::with_name("symmetry")
+                .long("symmetry")
+                .help("break symmetries found by the automorphism search before search"),
+        )
+        .arg(
+            Arg::with_name("symmetry-file")
+                .long("symmetry-file")
+                .takes_value(true)
+                .value_name("file")
+                .help("break symmetries of generators in the cycle notation in a file"),
+        )
+        .arg(
+            Arg::with_name("no-target-phase")
+                .long("no-target-phase")
+                .help("decide by saved phases instead of target phases"),
+        )
+        .arg(
+            Arg::with_name("rephase-interval")
+                .long("rephase-interval")
+                .takes_value(true)
+                .value_name("conflicts")
+                .help("the base interval of rephasing (default 1000, 0 disables rephasing)"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("the seed of random rephasing (default 0)"),
+        )
         .get_matches();
     let input = matches.value_of("input").expect("input is required");
-    let mut solver = Solver::new();
+    let config = Config {
+        restart: restart_strategy(&matches),
+        branching: parse_value::<BranchingStrategy>(&matches, "branching").unwrap_or_default(),
+        phase: phase_config(&matches),
+        chrono: if matches.is_present("no-chrono") {
+            None
+        } else {
+            Some(parse_value(&matches, "chrono").unwrap_or(100))
+        },
+        reuse_trail: !matches.is_present("no-reuse-trail"),
+        shrink: matches.is_present("shrink"),
+        otfs: !matches.is_present("no-otfs"),
+        eager_subsume: parse_value(&matches, "eager-subsume").unwrap_or(20),
+        vivify: !matches.is_present("no-vivify"),
+        probe: !matches.is_present("no-probe"),
+        subsume: !matches.is_present("no-subsume"),
+        unhide: !matches.is_present("no-unhide"),
+        scc: !matches.is_present("no-scc"),
+        bce: !matches.is_present("no-bce"),
+        cce: matches.is_present("cce"),
+        elim: !matches.is_present("no-elim"),
+        xor: !matches.is_present("no-xor"),
+        card: !matches.is_present("no-card"),
+        symmetry: matches.is_present("symmetry"),
+    };
+    let mut solver = Solver::with_config(config);
+    if let Some(file) = matches.value_of("symmetry-file") {
+        match parser::parse_symmetries(
+            std::fs::File::open(file).unwrap_or_else(|_| panic!("can't open file {}", file)),
+        ) {
+            Ok(generators) => generators
+                .iter()
+                .for_each(|generator| solver.add_symmetry(generator)),
+            Err(e) => {
+                eprintln!("{:?}", e);
+                panic!("failed to parse");
+            }
+        }
+    }
+    if input.ends_with(".opb") {
+        solve_opb(solver, input);
+        return;
+    }
+    if input.ends_with(".wcnf") {
+        let strategy = parse_value::<MaxSatStrategy>(&matches, "maxsat").unwrap_or_default();
+        solve_wcnf(solver, input, strategy);
+        return;
+    }
     match parser::parse_cnf(
         std::fs::File::open(input).unwrap_or_else(|_| panic!("can't open file {}", input)),
     ) {
@@ -33,17 +401,15 @@ fn main() {
             cnf.clauses.iter().for_each(|lits| {
                 solver.add_clause(lits);
             });
-
-            let sender = solver.sender.clone();
-            let mut signals = Signals::new(&[SIGINT]).expect("togatoga");
-            std::thread::spawn(move || {
-                for sig in signals.forever() {
-                    eprintln!("{:?}", sig);
-                    sender.send(0).expect("failed to send");
-                }
+            cnf.xors.iter().for_each(|lits| {
+                solver.add_xor(lits);
             });
 
-            match solver.solve() {
+            interrupt_on_sigint(&solver);
+
+            let result = solver.solve();
+            println!("{}", solver.stats);
+            match result {
                 scrapsat::core::SatResult::Sat => {
                     println!("c SAT");
                 }
@@ -9,26 +9,33 @@ pub struct CnfData {
     pub num_clause: Option<u32>,
     // all problem clauses
     pub clauses: Vec<Vec<Lit>>,
+    // XOR constraints that an odd number of literals are true
+    pub xors: Vec<Vec<Lit>>,
 }
 /// Parse a DIMACAS cnf file
 /// # Arguments
 /// * `input_file` - A path of an input file name
+///
 /// c Here is a comment.
 /// c SATISFIABLE
 /// p cnf 5 3
 /// 1 -5 4 0
 /// -1 5 3 4 0
 /// -3 -4 0
+/// x1 -2 3 0
+///
+/// A line that starts with `x` is an XOR constraint of CryptoMiniSat.
 pub fn parse_cnf<R: std::io::Read>(input: R) -> std::io::Result<CnfData> {
     let reader = std::io::BufReader::new(input);
     let mut num_variable = None;
     let mut num_clause = None;
     let mut clauses = vec![];
+    let mut xors = vec![];
 
     for line in reader.lines() {
         let line = line?;
         // trim extra/duplicate whitespaces
-        let values: Vec<_> = line.split_whitespace().into_iter().collect::<Vec<_>>();
+        let mut values: Vec<_> = line.split_whitespace().collect::<Vec<_>>();
         if values.is_empty() || values[0] == "c" {
             // empty or comment
             continue;
@@ -41,6 +48,14 @@ pub fn parse_cnf<R: std::io::Read>(input: R) -> std::io::Result<CnfData> {
                 continue;
             }
         }
+        // x1 -2 3 0 or x 1 -2 3 0
+        let xor = values[0].starts_with('x');
+        if xor {
+            values[0] = &values[0][1..];
+            if values[0].is_empty() {
+                values.remove(0);
+            }
+        }
 
         let mut ok = true;
         let lits: Vec<_> = values
@@ -60,11 +75,300 @@ pub fn parse_cnf<R: std::io::Read>(input: R) -> std::io::Result<CnfData> {
             continue;
         }
         let clause: Vec<Lit> = lits.iter().map(|&x| Lit::from(x)).collect();
-        clauses.push(clause);
+        if xor {
+            xors.push(clause);
+        } else {
+            clauses.push(clause);
+        }
     }
     Ok(CnfData {
         num_variable,
         num_clause,
         clauses,
+        xors,
+    })
+}
+
+/// OpbData represents parsed pseudo-Boolean constraints
+#[derive(Debug)]
+pub struct OpbData {
+    // the number of variable
+    pub num_variable: Option<u32>,
+    // the number of constraint
+    pub num_constraint: Option<u32>,
+    // the linear objective to minimize
+    pub objective: Option<Vec<(i64, Lit)>>,
+    // constraints that the sums of the coefficients of true literals are at least the degrees
+    pub constraints: Vec<(Vec<(i64, Lit)>, i64)>,
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Parse a literal `x1` or its negation `~x1` of OPB.
+fn parse_opb_lit(token: &str) -> std::io::Result<Lit> {
+    let (positive, name) = match token.strip_prefix('~') {
+        Some(name) => (false, name),
+        None => (true, token),
+    };
+    match name.strip_prefix('x').and_then(|x| x.parse::<u32>().ok()) {
+        Some(x) if x > 0 => Ok(Lit::new(x - 1, positive)),
+        _ => Err(invalid_data(format!("invalid literal: {}", token))),
+    }
+}
+
+/// Parse a weighted sum of literals `+2 x1 -3 ~x2`.
+/// A product of literals `+2 x1 x2` isn't supported.
+fn parse_opb_terms(tokens: &[&str]) -> std::io::Result<Vec<(i64, Lit)>> {
+    if !tokens.len().is_multiple_of(2) {
+        return Err(invalid_data(format!("invalid terms: {}", tokens.join(" "))));
+    }
+    tokens
+        .chunks(2)
+        .map(|term| {
+            let coefficient = term[0]
+                .parse::<i64>()
+                .map_err(|_| invalid_data(format!("invalid coefficient: {}", term[0])))?;
+            Ok((coefficient, parse_opb_lit(term[1])?))
+        })
+        .collect()
+}
+
+/// Parse an OPB file of the pseudo-Boolean competition
+/// # Arguments
+/// * `input` - A reader of an input file
+///
+/// ```text
+/// * #variable= 3 #constraint= 2
+/// min: +1 x1 +2 x2 -1 x3 ;
+/// +2 x1 +3 ~x2 >= 4 ;
+/// +1 x1 +1 x2 +1 x3 = 1 ;
+/// ```
+///
+/// A constraint of `=` is parsed as two constraints of `>=`, and `<=` is parsed as `>=`
+/// by negating coefficients.
+pub fn parse_opb<R: std::io::Read>(input: R) -> std::io::Result<OpbData> {
+    let reader = std::io::BufReader::new(input);
+    let mut num_variable = None;
+    let mut num_constraint = None;
+    let mut objective = None;
+    let mut constraints = vec![];
+
+    // A statement ends with `;` and can span lines.
+    let mut statement = String::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with('*') {
+            // * #variable= 3 #constraint= 2
+            let values: Vec<_> = line.split_whitespace().collect();
+            for w in values.windows(2) {
+                match w[0] {
+                    "#variable=" => num_variable = w[1].parse::<u32>().ok(),
+                    "#constraint=" => num_constraint = w[1].parse::<u32>().ok(),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        statement.push_str(&line);
+        statement.push(' ');
+        while let Some(end) = statement.find(';') {
+            let rest = statement.split_off(end + 1);
+            let current = std::mem::replace(&mut statement, rest);
+            let mut values: Vec<_> = current[..end].split_whitespace().collect();
+            if values.is_empty() {
+                continue;
+            }
+            if let Some(body) = values[0].strip_prefix("min:") {
+                // min: +1 x1 or min:+1 x1
+                if body.is_empty() {
+                    values.remove(0);
+                } else {
+                    values[0] = body;
+                }
+                objective = Some(parse_opb_terms(&values)?);
+                continue;
+            }
+            let (pos, relation) = values
+                .iter()
+                .enumerate()
+                .find(|(_, value)| [">=", "<=", "="].contains(value))
+                .map(|(pos, &value)| (pos, value))
+                .ok_or_else(|| invalid_data(format!("no relation: {}", values.join(" "))))?;
+            let terms = parse_opb_terms(&values[..pos])?;
+            let degree = match &values[pos + 1..] {
+                [degree] => degree
+                    .parse::<i64>()
+                    .map_err(|_| invalid_data(format!("invalid degree: {}", degree)))?,
+                _ => {
+                    return Err(invalid_data(format!(
+                        "invalid degree: {}",
+                        values.join(" ")
+                    )))
+                }
+            };
+            let negated = || terms.iter().map(|&(a, lit)| (-a, lit)).collect();
+            match relation {
+                ">=" => constraints.push((terms, degree)),
+                "<=" => constraints.push((negated(), -degree)),
+                _ => {
+                    constraints.push((negated(), -degree));
+                    constraints.push((terms, degree));
+                }
+            }
+        }
+    }
+    if !statement.trim().is_empty() {
+        return Err(invalid_data(format!("no `;`: {}", statement.trim())));
+    }
+    Ok(OpbData {
+        num_variable,
+        num_constraint,
+        objective,
+        constraints,
+    })
+}
+
+/// WcnfData represents parsed weighted partial MaxSAT data
+#[derive(Debug)]
+pub struct WcnfData {
+    // the number of variable
+    pub num_variable: Option<u32>,
+    // the number of clause
+    pub num_clause: Option<u32>,
+    // the weight of hard clauses in the old format
+    pub top: Option<u64>,
+    // clauses that must be satisfied
+    pub hards: Vec<Vec<Lit>>,
+    // clauses whose weights are paid if they are falsified
+    pub softs: Vec<(u64, Vec<Lit>)>,
+}
+
+/// Parse a clause of DIMACS ended with `0`.
+fn parse_wcnf_clause(values: &[&str]) -> std::io::Result<Vec<Lit>> {
+    let mut clause = Vec::with_capacity(values.len());
+    for value in values.iter() {
+        match value.parse::<i32>() {
+            Ok(0) => return Ok(clause),
+            Ok(x) => clause.push(Lit::from(x)),
+            Err(_) => return Err(invalid_data(format!("invalid literal: {}", value))),
+        }
+    }
+    Err(invalid_data(format!("no `0`: {}", values.join(" "))))
+}
+
+/// Parse a WCNF file of the MaxSAT evaluation
+/// # Arguments
+/// * `input` - A reader of an input file
+///
+/// The format since 2022 marks hard clauses with `h` and soft clauses with their weights.
+///
+/// ```text
+/// c Here is a comment.
+/// h 1 -2 0
+/// 3 2 0
+/// 5 -1 0
+/// ```
+///
+/// The old format has a header with the weight `top` of hard clauses.
+///
+/// ```text
+/// p wcnf 2 3 10
+/// 10 1 -2 0
+/// 3 2 0
+/// 5 -1 0
+/// ```
+///
+/// All clauses are soft if `top` is omitted.
+pub fn parse_wcnf<R: std::io::Read>(input: R) -> std::io::Result<WcnfData> {
+    let reader = std::io::BufReader::new(input);
+    let mut num_variable = None;
+    let mut num_clause = None;
+    let mut top = None;
+    let mut hards = vec![];
+    let mut softs = vec![];
+
+    for line in reader.lines() {
+        let line = line?;
+        let values: Vec<_> = line.split_whitespace().collect();
+        if values.is_empty() || values[0] == "c" {
+            // empty or comment
+            continue;
+        }
+        if values[0] == "p" {
+            // p wcnf 2 3 10
+            if values.len() < 4 || values.len() > 5 || values[1] != "wcnf" {
+                return Err(invalid_data(format!("invalid header: {}", line)));
+            }
+            num_variable = values[2].parse::<u32>().ok();
+            num_clause = values[3].parse::<u32>().ok();
+            top = values.get(4).and_then(|top| top.parse::<u64>().ok());
+            continue;
+        }
+        if values[0] == "h" {
+            hards.push(parse_wcnf_clause(&values[1..])?);
+            continue;
+        }
+        let weight = values[0]
+            .parse::<u64>()
+            .map_err(|_| invalid_data(format!("invalid weight: {}", values[0])))?;
+        let clause = parse_wcnf_clause(&values[1..])?;
+        if top.is_some_and(|top| weight >= top) {
+            hards.push(clause);
+        } else {
+            softs.push((weight, clause));
+        }
+    }
+    Ok(WcnfData {
+        num_variable,
+        num_clause,
+        top,
+        hards,
+        softs,
     })
 }
+
+/// Parse generators of symmetries in the cycle notation of saucy and BreakID
+/// # Arguments
+/// * `input` - A reader of an input file
+///
+/// ```text
+/// c A generator per line
+/// (1 2)(-1 -2)(3 -4)
+/// ( 5 6 7 ) ( -5 -6 -7 )
+/// ```
+///
+/// A cycle `(a b c)` maps `a` to `b`, `b` to `c` and `c` to `a`.
+/// Each generator is returned as pairs of literals and their images.
+pub fn parse_symmetries<R: std::io::Read>(input: R) -> std::io::Result<Vec<Vec<(Lit, Lit)>>> {
+    let reader = std::io::BufReader::new(input);
+    let mut generators = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('c') {
+            // empty or comment
+            continue;
+        }
+        if !line.starts_with('(') || !line.ends_with(')') {
+            return Err(invalid_data(format!("invalid generator: {}", line)));
+        }
+        let mut generator = vec![];
+        for cycle in line[1..line.len() - 1].split(')') {
+            let cycle = cycle.trim().strip_prefix('(').unwrap_or(cycle).trim();
+            let lits = cycle
+                .split_whitespace()
+                .map(|x| match x.parse::<i32>() {
+                    Ok(x) if x != 0 => Ok(Lit::from(x)),
+                    _ => Err(invalid_data(format!("invalid literal: {}", x))),
+                })
+                .collect::<std::io::Result<Vec<Lit>>>()?;
+            for (i, &lit) in lits.iter().enumerate() {
+                generator.push((lit, lits[(i + 1) % lits.len()]));
+            }
+        }
+        generators.push(generator);
+    }
+    Ok(generators)
+}
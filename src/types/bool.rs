@@ -1,7 +1,8 @@
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
 pub enum LitBool {
     True = 0,
     False = 1,
+    #[default]
     UnDef = 2,
 }
 
@@ -14,9 +15,3 @@ impl From<i8> for LitBool {
         }
     }
 }
-
-impl Default for LitBool {
-    fn default() -> Self {
-        LitBool::UnDef
-    }
-}
@@ -5,10 +5,14 @@ use super::{bool::LitBool, var::Var};
 pub struct Lit(u32);
 impl Lit {
     /// A `UNDEF` is a default lit.
-    pub const UNDEF: Lit = Lit(std::u32::MAX);
+    pub const UNDEF: Lit = Lit(u32::MAX);
     pub fn new(var: u32, positive: bool) -> Lit {
         Lit(if positive { var << 1 } else { (var << 1) + 1 })
     }
+    /// Returns the literal whose index in `LitVec` is `x`.
+    pub fn from_idx(x: usize) -> Lit {
+        Lit(x as u32)
+    }
     #[inline]
     pub fn var(self) -> Var {
         Var(self.0 >> 1)
@@ -70,7 +74,7 @@ impl From<i32> for Lit {
     #[inline]
     fn from(x: i32) -> Self {
         debug_assert!(x != 0, "0 can not be positive or negative");
-        let d = x.abs() as u32 - 1;
+        let d = x.unsigned_abs() - 1;
         if x > 0 {
             Lit(d << 1)
         } else {
@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod tests {
-    use scrapsat::types::lit::Lit;
+    use scrapsat::types::{lit::Lit, var::Var};
     use scrapsat::{
-        core::{SatResult, Solver},
+        core::{
+            branching::BranchingStrategy, config::Config, maxsat::MaxSatStrategy,
+            optimize::Optimum, phase::PhaseConfig, restart::RestartStrategy, SatResult, Solver,
+        },
         parser,
         types::bool::LitBool,
     };
@@ -12,17 +15,13 @@ mod tests {
             let mut satisfied = false;
             for lit in clause {
                 match assigns[lit.var().0 as usize] {
-                    LitBool::True => {
-                        if lit.pos() {
-                            satisfied = true;
-                            break;
-                        }
+                    LitBool::True if lit.pos() => {
+                        satisfied = true;
+                        break;
                     }
-                    LitBool::False => {
-                        if lit.neg() {
-                            satisfied = true;
-                            break;
-                        }
+                    LitBool::False if lit.neg() => {
+                        satisfied = true;
+                        break;
                     }
                     _ => {}
                 };
@@ -73,7 +72,7 @@ mod tests {
         } else {
             SatResult::Unsat
         };
-        let skip_cnfs = vec!["cnf/unsat/graph_color_unsat.cnf"];
+        let skip_cnfs = ["cnf/unsat/graph_color_unsat.cnf"];
         let entries = WalkDir::new(format!("cnf/{}/", which));
         for entry in entries
             .into_iter()
@@ -105,19 +104,19 @@ mod tests {
 
                 assert!(
                     result == expected,
-                    
-                        "cnf: {}, Result: {:?} Expected: {:?}",
-                        path_str, result, expected
-                    
+                    "cnf: {}, Result: {:?} Expected: {:?}",
+                    path_str,
+                    result,
+                    expected
                 );
 
                 if result == SatResult::Sat {
                     assert!(
                         sat_model_check(&cnf.clauses, &solver.models),
-                        
-                            "The models are wrong!! cnf: {}, Result: {:?} Expected: {:?}",
-                            path_str, result, expected
-                        
+                        "The models are wrong!! cnf: {}, Result: {:?} Expected: {:?}",
+                        path_str,
+                        result,
+                        expected
                     );
                 }
             }
@@ -128,4 +127,1052 @@ mod tests {
         test_all_files("sat");
         test_all_files("unsat");
     }
+
+    /// Returns a xorshift generator of random numbers from `seed`.
+    fn xorshift(seed: u64) -> impl FnMut() -> u64 {
+        let mut x = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        move || {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        }
+    }
+
+    /// Generate a random k-CNF with a xorshift generator.
+    fn random_kcnf(num_var: u32, num_clause: usize, k: usize, seed: u64) -> Vec<Vec<Lit>> {
+        let mut next = xorshift(seed);
+        (0..num_clause)
+            .map(|_| {
+                let mut clause: Vec<Lit> = Vec::new();
+                while clause.len() < k {
+                    let v = (next() % num_var as u64) as u32;
+                    if clause.iter().any(|lit| lit.var().0 == v) {
+                        continue;
+                    }
+                    clause.push(Lit::new(v, next().is_multiple_of(2)));
+                }
+                clause
+            })
+            .collect()
+    }
+
+    fn brute_force(clauses: &[Vec<Lit>], num_var: u32) -> bool {
+        (0..1u64 << num_var).any(|bits| {
+            clauses.iter().all(|clause| {
+                clause
+                    .iter()
+                    .any(|lit| ((bits >> lit.var().0) & 1 == 1) == lit.pos())
+            })
+        })
+    }
+
+    #[test]
+    fn test_solve_random_small() {
+        for seed in 0..200 {
+            let num_var = 12;
+            let clauses = random_kcnf(num_var, 55, 3, seed);
+            let mut solver = Solver::default();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let result = solver.solve();
+            let expected = if brute_force(&clauses, num_var) {
+                SatResult::Sat
+            } else {
+                SatResult::Unsat
+            };
+            assert_eq!(result, expected, "seed: {}", seed);
+            if result == SatResult::Sat {
+                assert!(sat_model_check(&clauses, &solver.models));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduce_db() {
+        for seed in 0..2 {
+            let clauses = random_kcnf(200, 860, 3, seed);
+            let mut solver = Solver::default();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let result = solver.solve();
+            if result == SatResult::Sat {
+                assert!(sat_model_check(&clauses, &solver.models));
+            }
+            assert!(solver.stats.reduce_dbs > 0);
+            assert!(solver.stats.removed_learnts > 0);
+            // learnt clauses are bounded by the reduction.
+            assert!((solver.num_learnts() as u64) < solver.stats.conflicts * 3 / 4);
+        }
+
+        // The instances in cnf/benchmark are git-LFS pointers, so a uniform random
+        // 3-SAT instance of the same size as uf250 is kept as a plain fixture.
+        let input = std::fs::File::open("tests/cnf/uf250-random-7.cnf").unwrap();
+        let cnf = parser::parse_cnf(input).unwrap();
+        let mut solver = Solver::default();
+        cnf.clauses
+            .iter()
+            .for_each(|clause| solver.add_clause(clause));
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert!(sat_model_check(&cnf.clauses, &solver.models));
+        assert!(solver.stats.reduce_dbs >= 2, "{}", solver.stats);
+        assert!(solver.stats.removed_learnts > 0);
+        assert!((solver.num_learnts() as u64) < solver.stats.conflicts * 3 / 4);
+    }
+
+    #[test]
+    fn test_solve_with_assumptions() {
+        let num_var = 12;
+        for seed in 0..30 {
+            let clauses = random_kcnf(num_var, 40, 3, seed);
+            let mut solver = Solver::default();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            for query in 0..30 {
+                let assumptions: Vec<Lit> =
+                    random_kcnf(num_var, 1, 1 + query as usize % 6, seed * 100 + query)
+                        .pop()
+                        .unwrap();
+                let result = solver.solve_with_assumptions(&assumptions);
+
+                let mut with_units = clauses.clone();
+                with_units.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = if brute_force(&with_units, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "seed: {} query: {}", seed, query);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&with_units, &solver.models));
+                    assert!(solver.failed_assumptions.is_empty());
+                } else {
+                    // a failed assumption core is a subset of assumptions and is still unsatisfiable.
+                    assert!(solver
+                        .failed_assumptions
+                        .iter()
+                        .all(|lit| assumptions.contains(lit)));
+                    let mut with_core = clauses.clone();
+                    with_core.extend(solver.failed_assumptions.iter().map(|&lit| vec![lit]));
+                    assert!(!brute_force(&with_core, num_var));
+                }
+            }
+            // the base formula is not affected by assumptions.
+            let expected = if brute_force(&clauses, num_var) {
+                SatResult::Sat
+            } else {
+                SatResult::Unsat
+            };
+            assert_eq!(solver.solve(), expected);
+        }
+    }
+
+    #[test]
+    fn test_incremental_add_clause() {
+        for seed in 0..20 {
+            let mut solver = Solver::default();
+            let mut clauses = Vec::new();
+            for i in 0..80u64 {
+                // variables are added gradually.
+                let num_var = 6 + i as u32 / 10;
+                clauses.extend(random_kcnf(num_var, 1, 3, seed * 1000 + i));
+                solver.add_clause(clauses.last().unwrap());
+                if i % 2 == 0 {
+                    continue;
+                }
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "seed: {} clauses: {}", seed, i + 1);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_interrupt_is_cleared() {
+        let clauses = random_kcnf(10, 20, 3, 0);
+        let mut solver = Solver::default();
+        clauses.iter().for_each(|clause| solver.add_clause(clause));
+        solver.sender.send(0).unwrap();
+        assert_eq!(solver.solve(), SatResult::Unknown);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert!(sat_model_check(&clauses, &solver.models));
+
+        let v = solver.new_var();
+        solver.add_clause(&[Lit::new(v.0, false)]);
+        assert_eq!(solver.solve(), SatResult::Sat);
+        assert_eq!(solver.models[v.0 as usize], LitBool::False);
+    }
+
+    #[test]
+    fn test_binary_clauses() {
+        for seed in 0..100 {
+            let num_var = 14;
+            // mostly binary clauses with a few ternary clauses
+            let mut clauses = random_kcnf(num_var, 20, 2, seed);
+            clauses.extend(random_kcnf(num_var, 10, 3, seed + 1000));
+            let mut solver = Solver::default();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let result = solver.solve();
+            let expected = if brute_force(&clauses, num_var) {
+                SatResult::Sat
+            } else {
+                SatResult::Unsat
+            };
+            assert_eq!(result, expected, "seed: {}", seed);
+            if result == SatResult::Sat {
+                assert!(sat_model_check(&clauses, &solver.models));
+            }
+        }
+    }
+
+    #[test]
+    fn test_restart_strategies() {
+        let strategies = [
+            RestartStrategy::luby(),
+            RestartStrategy::geometric(),
+            RestartStrategy::glucose(),
+            RestartStrategy::ema(),
+        ];
+        for strategy in strategies.iter() {
+            for seed in 0..50 {
+                let num_var = 14;
+                let clauses = random_kcnf(num_var, 60, 3, seed);
+                let mut solver = Solver::with_config(Config {
+                    restart: *strategy,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "{:?} seed: {}", strategy, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_branching_strategies() {
+        let strategies = [
+            BranchingStrategy::Vsids,
+            BranchingStrategy::Lrb,
+            BranchingStrategy::Chb,
+            BranchingStrategy::Vmtf,
+        ];
+        for strategy in strategies.iter() {
+            for seed in 0..50 {
+                let num_var = 14;
+                let clauses = random_kcnf(num_var, 60, 3, seed);
+                let mut solver = Solver::with_config(Config {
+                    branching: *strategy,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "{:?} seed: {}", strategy, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_rephasing() {
+        let phases = [
+            PhaseConfig::default(),
+            PhaseConfig {
+                target: false,
+                rephase_interval: 0,
+                seed: 0,
+            },
+            PhaseConfig {
+                target: true,
+                rephase_interval: 1,
+                seed: 7,
+            },
+        ];
+        for phase in phases.iter() {
+            for seed in 0..50 {
+                let num_var = 14;
+                let clauses = random_kcnf(num_var, 60, 3, seed);
+                // restart often to rephase often.
+                let mut solver = Solver::with_config(Config {
+                    restart: RestartStrategy::Luby { first: 1, inc: 2.0 },
+                    phase: *phase,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "{:?} seed: {}", phase, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_chronological_backtracking() {
+        let num_var = 14;
+        for seed in 0..50 {
+            let mut clauses = random_kcnf(num_var, 10, 2, seed);
+            clauses.extend(random_kcnf(num_var, 45, 3, seed + 1000));
+            // every backjump is replaced with a chronological backtrack.
+            let mut solver = Solver::with_config(Config {
+                chrono: Some(0),
+                ..Config::default()
+            });
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            for query in 0..10 {
+                let assumptions: Vec<Lit> =
+                    random_kcnf(num_var, 1, query as usize % 4, seed * 100 + query)
+                        .pop()
+                        .unwrap();
+                let result = solver.solve_with_assumptions(&assumptions);
+                let mut with_units = clauses.clone();
+                with_units.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = if brute_force(&with_units, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "seed: {} query: {}", seed, query);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&with_units, &solver.models));
+                } else {
+                    let mut with_core = clauses.clone();
+                    with_core.extend(solver.failed_assumptions.iter().map(|&lit| vec![lit]));
+                    assert!(!brute_force(&with_core, num_var));
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_reuse_trail() {
+        let num_var = 16;
+        let mut reused_levels = 0;
+        for seed in 0..50 {
+            let clauses = random_kcnf(num_var, 68, 3, seed);
+            let mut solver = Solver::with_config(Config {
+                restart: RestartStrategy::Geometric {
+                    first: 1,
+                    factor: 1.0,
+                },
+                reuse_trail: true,
+                ..Config::default()
+            });
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            for query in 0..5 {
+                let assumptions: Vec<Lit> =
+                    random_kcnf(num_var, 1, query, seed * 100 + query as u64)
+                        .pop()
+                        .unwrap();
+                let result = solver.solve_with_assumptions(&assumptions);
+                let mut with_units = clauses.clone();
+                with_units.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = if brute_force(&with_units, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "seed: {} query: {}", seed, query);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&with_units, &solver.models));
+                }
+            }
+            reused_levels += solver.stats.reused_levels;
+        }
+        assert!(reused_levels > 0);
+    }
+    #[test]
+    fn test_minimize_and_shrink() {
+        for shrink in [false, true] {
+            for seed in 0..100 {
+                let num_var = 16;
+                // binary clauses strengthen learnt clauses.
+                let mut clauses = random_kcnf(num_var, 12, 2, seed);
+                clauses.extend(random_kcnf(num_var, 50, 3, seed + 1000));
+                let mut solver = Solver::with_config(Config {
+                    shrink,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "shrink: {} seed: {}", shrink, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_otfs_and_eager_subsumption() {
+        let (mut strengthened, mut subsumed) = (0, 0);
+        for (otfs, eager_subsume) in [(false, 0), (true, 20)] {
+            for seed in 0..100 {
+                let num_var = 18;
+                let clauses = random_kcnf(num_var, 170, 4, seed);
+                let mut solver = Solver::with_config(Config {
+                    otfs,
+                    eager_subsume,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "otfs: {} seed: {}", otfs, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+                strengthened += solver.stats.otfs_strengthened;
+                subsumed += solver.stats.eager_subsumed;
+            }
+        }
+        assert!(strengthened > 0 && subsumed > 0);
+    }
+
+    #[test]
+    fn test_elimination_incremental() {
+        let mut eliminated = 0;
+        for seed in 0..100 {
+            let num_var = 14;
+            let mut clauses = random_kcnf(num_var, 50, 3, seed);
+            let mut solver = Solver::default();
+            // frozen variables are kept for assumptions.
+            solver.new_var();
+            solver.set_frozen(Var(0), true);
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let result = solver.solve();
+            eliminated += solver.stats.eliminated_vars;
+            assert!(!solver.is_eliminated(Var(0)));
+            let expected = if brute_force(&clauses, num_var) {
+                SatResult::Sat
+            } else {
+                SatResult::Unsat
+            };
+            assert_eq!(result, expected, "seed: {}", seed);
+            if result != SatResult::Sat {
+                continue;
+            }
+            assert!(sat_model_check(&clauses, &solver.models));
+
+            // clauses over eliminated variables restore them.
+            let added = random_kcnf(num_var, 10, 3, seed + 1000);
+            added.iter().for_each(|clause| solver.add_clause(clause));
+            clauses.extend(added);
+            let assumption = Lit::new(0, seed % 2 == 0);
+            let result = solver.solve_with_assumptions(&[assumption]);
+            let mut assumed = clauses.clone();
+            assumed.push(vec![assumption]);
+            let expected = if brute_force(&assumed, num_var) {
+                SatResult::Sat
+            } else {
+                SatResult::Unsat
+            };
+            assert_eq!(result, expected, "seed: {}", seed);
+            if result == SatResult::Sat {
+                assert!(sat_model_check(&assumed, &solver.models));
+            }
+        }
+        assert!(eliminated > 0);
+    }
+
+    #[test]
+    fn test_subsumption() {
+        let mut duplicates = 0;
+        for subsume in [false, true] {
+            for seed in 0..100 {
+                let num_var = 14;
+                let mut clauses = random_kcnf(num_var, 40, 3, seed);
+                // duplicates and clauses that are subsumed or strengthened by others
+                clauses.extend(random_kcnf(num_var, 10, 3, seed));
+                clauses.extend(random_kcnf(num_var, 10, 4, seed));
+                clauses.extend(random_kcnf(num_var, 10, 2, seed));
+                let mut solver = Solver::with_config(Config {
+                    subsume,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "subsume: {} seed: {}", subsume, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+                duplicates += solver.stats.duplicate_clauses;
+            }
+        }
+        assert!(duplicates > 0);
+    }
+
+    #[test]
+    fn test_blocked_and_covered_clauses() {
+        let (mut blocked, mut covered) = (0, 0);
+        for cce in [false, true] {
+            for seed in 0..200 {
+                let num_var = 14;
+                // sparse formulas have many blocked clauses.
+                let mut clauses = random_kcnf(num_var, 8, 2, seed);
+                clauses.extend(random_kcnf(num_var, 30, 3, seed + 1000));
+                let mut solver = Solver::with_config(Config {
+                    cce,
+                    elim: false,
+                    ..Config::default()
+                });
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                blocked += solver.stats.blocked_clauses;
+                covered += solver.stats.covered_clauses;
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "cce: {} seed: {}", cce, seed);
+                if result != SatResult::Sat {
+                    continue;
+                }
+                assert!(sat_model_check(&clauses, &solver.models));
+
+                // Removed clauses are restored by clauses and assumptions over their witnesses.
+                let added = random_kcnf(num_var, 10, 3, seed + 2000);
+                added.iter().for_each(|clause| solver.add_clause(clause));
+                clauses.extend(added);
+                let assumption = Lit::new((seed % num_var as u64) as u32, seed % 2 == 0);
+                let result = solver.solve_with_assumptions(&[assumption]);
+                clauses.push(vec![assumption]);
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "cce: {} seed: {}", cce, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+        assert!(blocked > 0 && covered > 0);
+    }
+
+    #[test]
+    fn test_equivalent_literals() {
+        let mut substituted = 0;
+        for scc in [false, true] {
+            for seed in 0..200 {
+                let num_var = 15;
+                let mut clauses = random_kcnf(num_var, 45, 3, seed);
+                // buffers and inverters between random variables
+                for (i, pair) in random_kcnf(num_var, 4, 2, seed + 1000).iter().enumerate() {
+                    let (x, y) = (pair[0], Lit::new(pair[1].var().0, i % 2 == 0));
+                    clauses.push(vec![!x, y]);
+                    clauses.push(vec![x, !y]);
+                }
+                let mut solver = Solver::with_config(Config {
+                    scc,
+                    ..Config::default()
+                });
+                solver.set_frozen(Var(0), true);
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                let result = solver.solve();
+                substituted += solver.stats.substituted_vars;
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "scc: {} seed: {}", scc, seed);
+                if result != SatResult::Sat {
+                    continue;
+                }
+                assert!(sat_model_check(&clauses, &solver.models));
+
+                // Substituted variables are restored by assumptions.
+                let assumptions = [
+                    Lit::new(0, seed % 2 == 0),
+                    Lit::new(1 + (seed % 14) as u32, true),
+                ];
+                let result = solver.solve_with_assumptions(&assumptions);
+                clauses.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "scc: {} seed: {}", scc, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+        assert!(substituted > 0);
+    }
+
+    /// Returns the clauses of an XOR constraint that an odd number of `lits` are true.
+    fn xor_to_cnf(lits: &[Lit]) -> Vec<Vec<Lit>> {
+        (0..1u32 << lits.len())
+            .filter(|bits| bits.count_ones() % 2 == 0)
+            .map(|bits| {
+                // the clause that forbids an even number of true literals
+                lits.iter()
+                    .enumerate()
+                    .map(|(i, &lit)| if bits >> i & 1 == 1 { !lit } else { lit })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns a line of DIMACS CNF.
+    fn dimacs_line(lits: &[Lit]) -> String {
+        let mut line = String::new();
+        for lit in lits.iter() {
+            let v = lit.var().0 as i32 + 1;
+            line.push_str(&format!("{} ", if lit.pos() { v } else { -v }));
+        }
+        line.push_str("0\n");
+        line
+    }
+
+    #[test]
+    fn test_xor_constraints() {
+        let mut detected = 0;
+        for xor in [false, true] {
+            for seed in 0..200 {
+                let num_var = 14;
+                let mut input = String::new();
+                let mut clauses = random_kcnf(num_var, 20, 3, seed);
+                for clause in clauses.iter() {
+                    input.push_str(&dimacs_line(clause));
+                }
+                // XOR constraints written as lines of `x` and as their clauses
+                let xors = random_kcnf(num_var, 6, 3 + (seed % 4) as usize, seed + 1000);
+                for (i, lits) in xors.iter().enumerate() {
+                    let encoded = xor_to_cnf(lits);
+                    if i % 2 == 0 {
+                        input.push('x');
+                        input.push_str(&dimacs_line(lits));
+                    } else {
+                        for clause in encoded.iter() {
+                            input.push_str(&dimacs_line(clause));
+                        }
+                    }
+                    clauses.extend(encoded);
+                }
+                let cnf = parser::parse_cnf(input.as_bytes()).unwrap();
+                assert_eq!(cnf.xors.len(), 3);
+                let mut solver = Solver::with_config(Config {
+                    xor,
+                    ..Config::default()
+                });
+                solver.set_frozen(Var(0), true);
+                cnf.clauses
+                    .iter()
+                    .for_each(|clause| solver.add_clause(clause));
+                cnf.xors.iter().for_each(|lits| solver.add_xor(lits));
+                let result = solver.solve();
+                detected += solver.stats.detected_xors;
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "xor: {} seed: {}", xor, seed);
+                if result != SatResult::Sat {
+                    continue;
+                }
+                assert!(sat_model_check(&clauses, &solver.models));
+
+                // XOR constraints are kept for assumptions and new constraints.
+                let lits = random_kcnf(num_var, 1, 4, seed + 2000).remove(0);
+                solver.add_xor(&lits);
+                clauses.extend(xor_to_cnf(&lits));
+                let assumptions = [
+                    Lit::new(0, seed % 2 == 0),
+                    Lit::new(1 + (seed % 13) as u32, true),
+                ];
+                let result = solver.solve_with_assumptions(&assumptions);
+                clauses.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "xor: {} seed: {}", xor, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+        assert!(detected > 0);
+    }
+
+    /// Returns the clauses of all `k + 1` subsets of `lits` that forbid them to be true together.
+    fn at_most_to_cnf(lits: &[Lit], k: usize) -> Vec<Vec<Lit>> {
+        (0..1u32 << lits.len())
+            .filter(|bits| bits.count_ones() as usize == k + 1)
+            .map(|bits| {
+                (0..lits.len())
+                    .filter(|i| bits >> i & 1 == 1)
+                    .map(|i| !lits[i])
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cardinality_constraints() {
+        let mut detected = 0;
+        for card in [false, true] {
+            for seed in 0..200 {
+                let num_var = 14;
+                let mut clauses = random_kcnf(num_var, 30, 3, seed);
+                let mut solver = Solver::with_config(Config {
+                    card,
+                    ..Config::default()
+                });
+                solver.set_frozen(Var(0), true);
+                clauses.iter().for_each(|clause| solver.add_clause(clause));
+                // at-most-k constraints added natively and as their clauses
+                let groups = random_kcnf(num_var, 4, 4 + (seed % 3) as usize, seed + 1000);
+                for (i, lits) in groups.iter().enumerate() {
+                    let k = 1 + (seed as usize + i) % 3;
+                    let encoded = at_most_to_cnf(lits, k);
+                    if i % 2 == 0 {
+                        solver.add_at_most(lits, k as u32);
+                    } else {
+                        encoded.iter().for_each(|clause| solver.add_clause(clause));
+                    }
+                    clauses.extend(encoded);
+                }
+                let result = solver.solve();
+                detected += solver.stats.detected_cards;
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "card: {} seed: {}", card, seed);
+                if result != SatResult::Sat {
+                    continue;
+                }
+                assert!(sat_model_check(&clauses, &solver.models));
+
+                // Cardinality constraints are kept for assumptions and new constraints.
+                let lits = random_kcnf(num_var, 1, 5, seed + 2000).remove(0);
+                solver.add_at_most(&lits, 2);
+                clauses.extend(at_most_to_cnf(&lits, 2));
+                let assumptions = [
+                    Lit::new(0, seed % 2 == 0),
+                    Lit::new(1 + (seed % 13) as u32, true),
+                ];
+                let result = solver.solve_with_assumptions(&assumptions);
+                clauses.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let expected = if brute_force(&clauses, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "card: {} seed: {}", card, seed);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&clauses, &solver.models));
+                }
+            }
+        }
+        assert!(detected > 0);
+    }
+
+    /// Returns a weighted sum of literals of OPB.
+    fn opb_terms(terms: &[(i64, Lit)]) -> String {
+        let mut line = String::new();
+        for &(a, lit) in terms.iter() {
+            let v = lit.var().0 + 1;
+            line.push_str(&format!(
+                "{:+} {}x{} ",
+                a,
+                if lit.pos() { "" } else { "~" },
+                v
+            ));
+        }
+        line
+    }
+
+    #[test]
+    fn test_opb_minimization() {
+        let mut found = 0;
+        for seed in 0..300 {
+            let num_var = 12;
+            let mut next = xorshift(seed as u64 + 1);
+            let mut random_terms = |len: usize| -> Vec<(i64, Lit)> {
+                (0..len)
+                    .map(|_| {
+                        let lit = Lit::new((next() % num_var) as u32, next().is_multiple_of(2));
+                        ((next() % 9) as i64 - 3, lit)
+                    })
+                    .collect()
+            };
+            let objective = random_terms(8);
+            let constraints: Vec<_> = (0..6)
+                .map(|i| {
+                    let relation = [">=", "<=", ">=", "<=", ">=", "="][i];
+                    (random_terms(5), relation, (seed + i as i64) % 3 - 1)
+                })
+                .collect();
+            let mut input = format!("* #variable= {} #constraint= 6\n", num_var);
+            input.push_str(&format!("min: {};\n", opb_terms(&objective)));
+            for (terms, relation, degree) in constraints.iter() {
+                input.push_str(&format!("{}{} {} ;\n", opb_terms(terms), relation, degree));
+            }
+            let opb = parser::parse_opb(input.as_bytes()).unwrap();
+            assert_eq!(opb.num_variable, Some(num_var as u32));
+            assert_eq!(opb.objective.as_ref(), Some(&objective));
+
+            let mut solver = Solver::new();
+            opb.constraints
+                .iter()
+                .for_each(|(terms, degree)| solver.add_pb(terms, *degree));
+            let optimum = solver.minimize(&objective, |_| {});
+
+            let sum = |terms: &[(i64, Lit)], bits: u64| -> i64 {
+                terms
+                    .iter()
+                    .filter(|(_, lit)| ((bits >> lit.var().0) & 1 == 1) == lit.pos())
+                    .map(|&(a, _)| a)
+                    .sum()
+            };
+            let expected = (0..1u64 << num_var)
+                .filter(|&bits| {
+                    constraints.iter().all(|(terms, relation, degree)| {
+                        let value = sum(terms, bits);
+                        match *relation {
+                            ">=" => value >= *degree,
+                            "<=" => value <= *degree,
+                            _ => value == *degree,
+                        }
+                    })
+                })
+                .map(|bits| sum(&objective, bits))
+                .min();
+            match expected {
+                Some(cost) => {
+                    found += 1;
+                    assert_eq!(optimum, Optimum::Found(cost), "seed: {}", seed);
+                    assert_eq!(solver.cost(&objective), cost);
+                    let bits = (0..num_var)
+                        .filter(|&v| solver.models.get(v as usize) == Some(&LitBool::True))
+                        .fold(0, |bits, v| bits | 1 << v);
+                    assert!(opb
+                        .constraints
+                        .iter()
+                        .all(|(terms, degree)| sum(terms, bits) >= *degree));
+                }
+                None => assert_eq!(optimum, Optimum::Unsat, "seed: {}", seed),
+            }
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn test_wcnf_maxsat() {
+        for seed in 0..100 {
+            let num_var = 10;
+            let mut next = xorshift(seed + 1);
+            let mut random_clause = |len: u64| -> Vec<Lit> {
+                (0..len)
+                    .map(|_| Lit::new((next() % num_var) as u32, next().is_multiple_of(2)))
+                    .collect()
+            };
+            let hards: Vec<Vec<Lit>> = (0..12).map(|_| random_clause(3)).collect();
+            let softs: Vec<(u64, Vec<Lit>)> = (0..16)
+                .map(|i| ((seed + i) % 5 * 4 + 1, random_clause(i % 3 + 1)))
+                .collect();
+
+            // the format since 2022 and the old format with `top`
+            let mut input = String::from("c random MaxSAT\n");
+            let top = softs.iter().map(|&(w, _)| w).sum::<u64>() + 1;
+            let mut old = format!("p wcnf {} {} {}\n", num_var, hards.len() + softs.len(), top);
+            for clause in hards.iter() {
+                input.push_str(&format!("h {}", dimacs_line(clause)));
+                old.push_str(&format!("{} {}", top, dimacs_line(clause)));
+            }
+            for (w, clause) in softs.iter() {
+                input.push_str(&format!("{} {}", w, dimacs_line(clause)));
+                old.push_str(&format!("{} {}", w, dimacs_line(clause)));
+            }
+            let wcnf = parser::parse_wcnf(input.as_bytes()).unwrap();
+            assert_eq!(wcnf.hards, hards);
+            assert_eq!(wcnf.softs, softs);
+            let old = parser::parse_wcnf(old.as_bytes()).unwrap();
+            assert_eq!(old.num_variable, Some(num_var as u32));
+            assert_eq!(old.top, Some(top));
+            assert_eq!(old.hards, hards);
+            assert_eq!(old.softs, softs);
+
+            let satisfied = |clause: &Vec<Lit>, bits: u64| {
+                clause
+                    .iter()
+                    .any(|lit| ((bits >> lit.var().0) & 1 == 1) == lit.pos())
+            };
+            let expected = (0..1u64 << num_var)
+                .filter(|&bits| hards.iter().all(|clause| satisfied(clause, bits)))
+                .map(|bits| {
+                    softs
+                        .iter()
+                        .filter(|(_, clause)| !satisfied(clause, bits))
+                        .map(|&(w, _)| w as i64)
+                        .sum::<i64>()
+                })
+                .min();
+            for &strategy in [MaxSatStrategy::CoreGuided, MaxSatStrategy::Linear].iter() {
+                let mut solver = Solver::new();
+                wcnf.hards
+                    .iter()
+                    .for_each(|clause| solver.add_clause(clause));
+                let optimum = solver.solve_maxsat(&wcnf.softs, strategy, |_| {});
+                match expected {
+                    Some(cost) => {
+                        assert_eq!(optimum, Optimum::Found(cost), "seed: {}", seed);
+                        assert_eq!(solver.soft_cost(&softs), cost);
+                        assert!(sat_model_check(&hards, &solver.models));
+                    }
+                    None => assert_eq!(optimum, Optimum::Unsat, "seed: {}", seed),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetry_breaking() {
+        for &(pigeons, holes) in [(7, 6), (6, 6)].iter() {
+            let x = |p: i32, h: i32| p * holes + h + 1;
+            let mut input = String::from("c swaps of adjacent pigeons and holes\n");
+            for p in 0..pigeons - 1 {
+                for h in 0..holes {
+                    input.push_str(&format!("({} {})", x(p, h), x(p + 1, h)));
+                }
+                input.push('\n');
+            }
+            for h in 0..holes - 1 {
+                for p in 0..pigeons {
+                    input.push_str(&format!("( {} {} ) ", -x(p, h), -x(p, h + 1)));
+                }
+                input.push('\n');
+            }
+            let generators = parser::parse_symmetries(input.as_bytes()).unwrap();
+            assert_eq!(generators.len() as i32, pigeons + holes - 2);
+            assert_eq!(
+                generators[0][..2],
+                [
+                    (Lit::from(1), Lit::from(x(1, 0))),
+                    (Lit::from(x(1, 0)), Lit::from(1))
+                ]
+            );
+
+            let mut clauses: Vec<Vec<Lit>> = (0..pigeons)
+                .map(|p| (0..holes).map(|h| Lit::from(x(p, h))).collect())
+                .collect();
+            for h in 0..holes {
+                for p in 0..pigeons {
+                    for q in p + 1..pigeons {
+                        clauses.push(vec![Lit::from(-x(p, h)), Lit::from(-x(q, h))]);
+                    }
+                }
+            }
+            let mut solver = Solver::new();
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            generators
+                .iter()
+                .for_each(|generator| solver.add_symmetry(generator));
+            let result = solver.solve();
+            assert_eq!(solver.stats.symmetry_generators, generators.len() as u64);
+            if pigeons > holes {
+                assert_eq!(result, SatResult::Unsat);
+            } else {
+                assert_eq!(result, SatResult::Sat);
+                assert!(sat_model_check(&clauses, &solver.models));
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_symmetry_breaking() {
+        let mut generators = 0;
+        for seed in 0..100 {
+            // Clauses closed under the swaps of variables `2i` and `2i + 1`.
+            let num_var = 12;
+            let swap = |lit: &Lit| Lit::new(lit.var().0 ^ 1, lit.pos());
+            let mut clauses = random_kcnf(num_var, 12, 3, seed);
+            let images: Vec<Vec<Lit>> = clauses
+                .iter()
+                .map(|clause| clause.iter().map(swap).collect())
+                .collect();
+            clauses.extend(images);
+            let mut solver = Solver::with_config(Config {
+                symmetry: true,
+                ..Config::default()
+            });
+            clauses.iter().for_each(|clause| solver.add_clause(clause));
+            let expected = if brute_force(&clauses, num_var) {
+                SatResult::Sat
+            } else {
+                SatResult::Unsat
+            };
+            assert_eq!(solver.solve(), expected, "seed: {}", seed);
+            generators += solver.stats.symmetry_generators;
+
+            // Clauses and assumptions over moved variables break the symmetries.
+            let added = random_kcnf(num_var, 8, 2, seed + 1000);
+            for (i, pair) in added.chunks(2).enumerate() {
+                pair.iter().for_each(|clause| solver.add_clause(clause));
+                clauses.extend(pair.iter().cloned());
+                let assumptions: Vec<Lit> = random_kcnf(num_var, 2, 1, seed + 2000 + i as u64)
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                let mut constraints = clauses.clone();
+                constraints.extend(assumptions.iter().map(|&lit| vec![lit]));
+                let result = solver.solve_with_assumptions(&assumptions);
+                let expected = if brute_force(&constraints, num_var) {
+                    SatResult::Sat
+                } else {
+                    SatResult::Unsat
+                };
+                assert_eq!(result, expected, "seed: {}, step: {}", seed, i);
+                if result == SatResult::Sat {
+                    assert!(sat_model_check(&constraints, &solver.models));
+                }
+            }
+        }
+        assert!(generators > 0);
+    }
 }